@@ -0,0 +1,291 @@
+use std::{
+    ffi::{OsStr, OsString},
+    io::Write,
+    path::Path,
+};
+
+use anyhow::Result;
+use clap::{ArgMatches, Command};
+
+#[cfg(feature = "basename")]
+use crate::applets::basename::Basename;
+#[cfg(feature = "cat")]
+use crate::applets::cat::Cat;
+#[cfg(feature = "clear")]
+use crate::applets::clear::Clear;
+#[cfg(feature = "diag")]
+use crate::applets::diag::Diag;
+#[cfg(feature = "dirname")]
+use crate::applets::dirname::Dirname;
+#[cfg(feature = "echo")]
+use crate::applets::echo::Echo;
+#[cfg(feature = "env")]
+use crate::applets::env::Env;
+#[cfg(feature = "expand")]
+use crate::applets::expand::Expand;
+#[cfg(feature = "false")]
+use crate::applets::r#false::False;
+#[cfg(feature = "find")]
+use crate::applets::find::Find;
+#[cfg(feature = "grep")]
+use crate::applets::grep::Grep;
+#[cfg(feature = "ls")]
+use crate::applets::ls::Ls;
+#[cfg(feature = "nproc")]
+use crate::applets::nproc::Nproc;
+#[cfg(feature = "nsenter")]
+use crate::applets::nsenter::Nsenter;
+#[cfg(feature = "pathmunge")]
+use crate::applets::pathmunge::Pathmunge;
+#[cfg(feature = "sed")]
+use crate::applets::sed::Sed;
+#[cfg(feature = "sleep")]
+use crate::applets::sleep::Sleep;
+#[cfg(feature = "stem")]
+use crate::applets::stem::Stem;
+#[cfg(feature = "true")]
+use crate::applets::r#true::True;
+#[cfg(feature = "uname")]
+use crate::applets::uname::{Arch, Uname};
+#[cfg(feature = "yes")]
+use crate::applets::yes::Yes;
+use crate::{
+    applets::{Streams, ln::Ln, mkdir::Mkdir, sh::Sh, which::Which},
+    config,
+    error::{self, status},
+};
+
+/// A utility that can be invoked as `rizzybox NAME` or through a link named NAME
+pub trait Applet: Sync {
+    /// The name the applet is installed and invoked as
+    fn name(&self) -> &'static str;
+
+    /// Other names the applet answers to
+    fn aliases(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Whether the applet can share the process with others, e.g. as a thread of
+    /// a shell pipeline. Applets that change process-wide state must opt out.
+    fn in_process(&self) -> bool {
+        true
+    }
+
+    /// The clap definition of the applet's arguments
+    fn command(&self) -> Command;
+
+    /// Rewrite the arguments after the applet's name at `args[at]` before they are
+    /// parsed, for syntax clap can't express, like the `-i[SUFFIX]` of `sed`
+    fn rewrite_args(&self, _args: &mut Vec<OsString>, _at: usize) {}
+
+    /// Add values that are only known at runtime, like the themes bat was built with,
+    /// as candidates to the arguments of `command` for shell completions
+    fn complete(&self, command: Command) -> Command {
+        command
+    }
+
+    /// The status to exit with when the applet is given arguments it can't parse.
+    /// Coreutils mostly use 1, but e.g. `ls` uses 2 and `env` 125 so that their
+    /// own failures can be told apart from those of what they ran.
+    fn usage_status(&self) -> i32 {
+        status::FAILURE
+    }
+
+    /// Run the applet with arguments parsed by [`Applet::command`], returning its exit status.
+    /// Failures that only concern some operands are reported on `streams.stderr` and
+    /// make the applet carry on with the rest, returning 1 at the end; anything
+    /// returned as `Err` is reported by the caller, through [`exit_status`].
+    fn run(&self, matches: &ArgMatches, streams: &mut Streams) -> Result<i32>;
+}
+
+/// Every applet that rizzybox was built with. `ln`, `mkdir`, `sh` and `which` are
+/// always included since `--install-self` and `debug` rely on them.
+pub static APPLETS: &[&dyn Applet] = &[
+    #[cfg(feature = "uname")]
+    &Arch,
+    #[cfg(feature = "basename")]
+    &Basename,
+    #[cfg(feature = "cat")]
+    &Cat,
+    #[cfg(feature = "clear")]
+    &Clear,
+    #[cfg(feature = "diag")]
+    &Diag,
+    #[cfg(feature = "dirname")]
+    &Dirname,
+    #[cfg(feature = "echo")]
+    &Echo,
+    #[cfg(feature = "env")]
+    &Env,
+    #[cfg(feature = "expand")]
+    &Expand,
+    #[cfg(feature = "false")]
+    &False,
+    #[cfg(feature = "find")]
+    &Find,
+    #[cfg(feature = "grep")]
+    &Grep,
+    &Ln,
+    #[cfg(feature = "ls")]
+    &Ls,
+    &Mkdir,
+    #[cfg(feature = "nproc")]
+    &Nproc,
+    #[cfg(feature = "nsenter")]
+    &Nsenter,
+    #[cfg(feature = "pathmunge")]
+    &Pathmunge,
+    #[cfg(feature = "sed")]
+    &Sed,
+    &Sh,
+    #[cfg(feature = "sleep")]
+    &Sleep,
+    #[cfg(feature = "stem")]
+    &Stem,
+    #[cfg(feature = "true")]
+    &True,
+    #[cfg(feature = "uname")]
+    &Uname,
+    &Which,
+    #[cfg(feature = "yes")]
+    &Yes,
+];
+
+/// Find the applet that is invoked as `name`, either by its name or one of its aliases
+pub fn find(name: &str) -> Option<&'static dyn Applet> {
+    APPLETS
+        .iter()
+        .find(|applet| applet.name() == name || applet.aliases().contains(&name))
+        .copied()
+}
+
+/// Names of the multicall binary itself, which take the applet as their first argument.
+/// `docker-debug` and `docker-rebug` are what the Docker CLI runs rizzybox as when it
+/// is installed as a plugin, and `busybox` lets scripts written for it run unchanged.
+pub const MULTICALL_NAMES: &[&str] = &["rizzybox", "busybox", "docker-debug", "docker-rebug"];
+
+/// Prefixes that may precede an applet's name in argv[0], e.g. `rz-cat`. More can be
+/// given in `RIZZYBOX_PREFIXES`, separated by `:`.
+pub const APPLET_PREFIXES: &[&str] = &["rizzybox-", "rz-"];
+
+/// Names that stand for an applet invoked with some arguments up front. `ll` is as
+/// close to the usual `ls -alF` as rizzybox's `ls` gets.
+#[cfg(feature = "ls")]
+pub const ARGV0_ALIASES: &[(&str, &[&str])] = &[("ll", &["ls", "-a"])];
+#[cfg(not(feature = "ls"))]
+pub const ARGV0_ALIASES: &[(&str, &[&str])] = &[];
+
+/// The subcommand and leading arguments that invoking rizzybox as `argv0` stands for,
+/// or `None` when it was invoked as the multicall binary. Only the exact basename of
+/// `argv0` is considered, optionally behind one of the [`APPLET_PREFIXES`].
+pub fn resolve_argv0(argv0: &OsStr) -> Option<Vec<String>> {
+    let name = Path::new(argv0).file_name()?.to_str()?;
+    if MULTICALL_NAMES.contains(&name) {
+        return None;
+    }
+    if let Some((_, args)) = ARGV0_ALIASES.iter().find(|(alias, _)| *alias == name) {
+        return Some(args.iter().map(|arg| arg.to_string()).collect());
+    }
+
+    let extra_prefixes = std::env::var("RIZZYBOX_PREFIXES").unwrap_or_default();
+    let prefixes = APPLET_PREFIXES.iter().copied().chain(
+        extra_prefixes
+            .split(':')
+            .filter(|prefix| !prefix.is_empty()),
+    );
+    std::iter::once(name)
+        .chain(prefixes.filter_map(|prefix| name.strip_prefix(prefix)))
+        .find(|name| find(name).is_some() || config::get().aliases.contains_key(*name))
+        .map(|name| vec![name.to_string()])
+}
+
+/// The clap command of an applet, with its aliases and configured defaults attached
+pub fn command(applet: &dyn Applet) -> Command {
+    let command = applet
+        .command()
+        .name(applet.name())
+        .visible_aliases(applet.aliases());
+    config::get().apply(command)
+}
+
+/// The status `applet` exits with for `result`, printing its error as `applet: target: reason`
+pub fn exit_status(applet: &dyn Applet, result: Result<i32>, stderr: &mut dyn Write) -> i32 {
+    match result {
+        Ok(status) => status,
+        Err(e) => {
+            let (status, message) = error::describe(&e);
+            if let Some(message) = message {
+                let _ = writeln!(stderr, "{}: {message}", applet.name());
+            }
+            status
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_cmd::Command;
+    use std::collections::HashSet;
+
+    use super::{APPLETS, ARGV0_ALIASES, MULTICALL_NAMES};
+
+    #[test]
+    fn names_and_aliases_are_unique() {
+        // Arrange
+        let mut seen = HashSet::new();
+
+        // Act
+        let names = APPLETS
+            .iter()
+            .flat_map(|applet| {
+                std::iter::once(applet.name()).chain(applet.aliases().iter().copied())
+            })
+            .chain(ARGV0_ALIASES.iter().map(|(alias, _)| *alias))
+            .chain(MULTICALL_NAMES.iter().copied());
+
+        // Assert
+        for name in names {
+            assert!(seen.insert(name), "`{name}` is registered more than once");
+        }
+    }
+
+    #[test]
+    fn list_matches_registry() {
+        // Arrange
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+        let names: Vec<&str> = APPLETS.iter().map(|applet| applet.name()).collect();
+
+        // Act
+        cmd.arg("--list");
+
+        // Assert
+        cmd.assert().success();
+        cmd.assert().stdout(format!("{}\n", names.join(" ")));
+    }
+
+    #[test]
+    fn list_as_json() {
+        // Arrange
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.args(["--list", "--json"]);
+
+        // Assert
+        let output = cmd.assert().success().get_output().stdout.clone();
+        let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+        let names: Vec<&str> = json
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|applet| applet["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            names,
+            APPLETS
+                .iter()
+                .map(|applet| applet.name())
+                .collect::<Vec<_>>()
+        );
+    }
+}
@@ -0,0 +1,231 @@
+use std::{
+    ffi::{OsStr, OsString},
+    io::Write,
+    os::unix::ffi::OsStrExt,
+};
+
+use anyhow::Result;
+use clap::{ArgMatches, Command, CommandFactory, FromArgMatches, Parser};
+
+use super::Streams;
+use crate::applet::Applet;
+
+/// Print NAME with any leading directory components removed
+#[derive(Parser)]
+pub struct BasenameArgs {
+    /// support multiple arguments and treat each as a NAME
+    #[arg(long = "multiple", short = 'a', visible_short_alias = 'm')]
+    pub multiple: bool,
+
+    /// the NAME of the directory to use
+    pub name: Vec<OsString>,
+
+    #[arg(long, short, help = "remove a trailing SUFFIX; implies -a")]
+    pub suffix: Option<OsString>,
+
+    /// end each output line with NUL, not newline
+    #[arg(long, short, visible_short_alias = '0')]
+    pub zero: bool,
+}
+
+pub struct Basename;
+
+impl Applet for Basename {
+    fn name(&self) -> &'static str {
+        "basename"
+    }
+
+    fn command(&self) -> Command {
+        BasenameArgs::command()
+    }
+
+    fn run(&self, matches: &ArgMatches, streams: &mut Streams) -> Result<i32> {
+        let args = BasenameArgs::from_arg_matches(matches)?;
+        basename_command(&args, &mut streams.stdout)?;
+        Ok(0)
+    }
+}
+
+pub fn basename_command(args: &BasenameArgs, out: &mut impl Write) -> Result<()> {
+    // a suffix only makes sense for more than one NAME
+    let multiple = args.multiple || args.suffix.is_some();
+    let names = if multiple {
+        &args.name[..]
+    } else {
+        &args.name[..args.name.len().min(1)]
+    };
+
+    for name in names {
+        let output = basename(name, args.suffix.as_deref());
+        out.write_all(output.as_bytes())?;
+        out.write_all(if args.zero { b"\0" } else { b"\n" })?;
+    }
+    Ok(())
+}
+
+/// `name` with any leading directory components removed, and `suffix` too if given.
+/// Names are taken as bytes, so ones that aren't valid UTF-8 come out unchanged.
+pub fn basename<'a>(name: &'a OsStr, suffix: Option<&OsStr>) -> &'a OsStr {
+    let name = name.as_bytes();
+    let mut base = name
+        .iter()
+        .rposition(|&byte| byte == b'/')
+        .map_or(name, |slash| &name[slash + 1..]);
+    if let Some(suffix) = suffix
+        .map(OsStr::as_bytes)
+        .filter(|suffix| !suffix.is_empty())
+    {
+        while let Some(stem) = base.strip_suffix(suffix) {
+            base = stem;
+        }
+    }
+    OsStr::from_bytes(base)
+}
+
+#[cfg(test)]
+mod tests {
+    #![expect(non_snake_case)]
+
+    use assert_cmd::{Command, cargo::cargo_bin, prelude::*};
+    use core::str;
+    use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+    use super::{BasenameArgs, basename_command};
+
+    const LONG_PATH: &str = "/var/home/username/.local/bin/mybinary";
+    const ANOTHER_PATH: &str = "/var/home/username/.local/bin/mycoolerbinary";
+    const PATH_WITH_SUFFIX: &str = "/var/home/username/git/coolproject/main.c";
+
+    #[test]
+    fn success() {
+        // Arrange
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.arg("basename");
+        cmd.arg(LONG_PATH);
+
+        // Assert
+        cmd.assert().success();
+        cmd.assert().stdout("mybinary\n");
+    }
+
+    #[test]
+    fn with___multiple_arg() {
+        // Arrange
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.arg("basename");
+        cmd.arg("--multiple");
+        cmd.arg(LONG_PATH);
+        cmd.arg(ANOTHER_PATH);
+
+        // Assert
+        cmd.assert().success();
+        cmd.assert().stdout("mybinary\nmycoolerbinary\n");
+    }
+
+    #[test]
+    fn with___suffix_arg() {
+        // Arrange
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.arg("basename");
+        cmd.arg("--suffix");
+        cmd.arg(".c");
+        cmd.arg(PATH_WITH_SUFFIX);
+
+        // Assert
+        cmd.assert().success();
+        cmd.assert().stdout("main\n");
+    }
+
+    #[test]
+    fn with___zero_arg() {
+        // Arrange
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.arg("basename");
+        cmd.arg("--zero");
+        cmd.arg(LONG_PATH);
+
+        // Assert
+        cmd.assert().success();
+        cmd.assert()
+            .stdout(predicates::str::ends_with("mybinary\0"));
+        // TODO: figure out how to assert the absence of a newline
+    }
+
+    #[test]
+    fn with_all_args() {
+        // Arrange
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.arg("basename");
+        cmd.arg("-a"); // --multiple
+        cmd.arg("-s"); // --suffix
+        cmd.arg(".c");
+        cmd.arg("-z"); // --zero
+        cmd.arg(LONG_PATH);
+        cmd.arg(ANOTHER_PATH);
+        cmd.arg(PATH_WITH_SUFFIX);
+
+        // Assert
+        cmd.assert().success();
+        cmd.assert().stdout(predicates::str::contains(
+            "mybinary\0mycoolerbinary\0main\0",
+        ));
+    }
+
+    #[test]
+    fn strips_directories_in_process() {
+        // Arrange
+        let args = BasenameArgs {
+            multiple: false,
+            name: vec![PATH_WITH_SUFFIX.into(), LONG_PATH.into()],
+            suffix: Some(".c".into()),
+            zero: false,
+        };
+        let mut out = Vec::new();
+
+        // Act
+        basename_command(&args, &mut out).unwrap();
+
+        // Assert
+        assert_eq!(out, b"main\nmybinary\n");
+    }
+
+    #[test]
+    fn keeps_invalid_utf8_names() {
+        // Arrange
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.arg("basename");
+        cmd.arg(OsStr::from_bytes(b"/tmp/caf\xe9.txt"));
+        cmd.args(["--suffix", ".txt"]);
+
+        // Assert
+        cmd.assert().success();
+        cmd.assert().stdout(&b"caf\xe9\n"[..]);
+    }
+
+    #[test]
+    fn stops_at_closed_pipe() {
+        // Arrange
+        let mut cmd = std::process::Command::new(cargo_bin(env!("CARGO_PKG_NAME")));
+
+        // Act
+        cmd.args(["basename", "/usr/lib"])
+            .stdout(crate::closed_pipe());
+
+        // Assert
+        cmd.assert()
+            .code(crate::error::status::BROKEN_PIPE)
+            .stderr("");
+    }
+}
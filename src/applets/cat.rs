@@ -0,0 +1,269 @@
+#[cfg(feature = "highlight")]
+use std::ffi::OsStr;
+use std::{
+    ffi::OsString,
+    fs::File,
+    io::{self, Read, Write},
+    path::Path,
+};
+
+use anyhow::Result;
+#[cfg(feature = "highlight")]
+use bat::PrettyPrinter;
+use clap::{ArgMatches, Command, CommandFactory, FromArgMatches, Parser};
+
+#[cfg(feature = "highlight")]
+use super::pretty_print;
+use super::{HighlightArgs, Streams};
+use crate::{
+    applet::Applet,
+    error::{self, Error},
+};
+
+/// Concatenate and print file contents
+#[derive(Parser)]
+pub struct CatArgs {
+    /// file to concatenate
+    pub file: Vec<OsString>,
+
+    #[command(flatten)]
+    pub highlight: HighlightArgs,
+
+    /// show non-printable characters
+    #[cfg(feature = "highlight")]
+    #[arg(long, short = 'A', default_value = "false")]
+    pub show_all: bool,
+
+    /// list available themes
+    #[cfg(feature = "highlight")]
+    #[arg(long)]
+    pub list_themes: bool,
+
+    /// number all output lines
+    #[cfg(feature = "highlight")]
+    #[arg(long, short)]
+    pub number_lines: bool,
+}
+
+pub struct Cat;
+
+impl Applet for Cat {
+    fn name(&self) -> &'static str {
+        "cat"
+    }
+
+    fn command(&self) -> Command {
+        CatArgs::command()
+    }
+
+    #[cfg(feature = "highlight")]
+    fn complete(&self, command: Command) -> Command {
+        super::complete_highlight_args(command)
+    }
+
+    fn run(&self, matches: &ArgMatches, streams: &mut Streams) -> Result<i32> {
+        let args = CatArgs::from_arg_matches(matches)?;
+
+        #[cfg(feature = "highlight")]
+        if args.show_all || args.list_themes || args.number_lines || streams.stdout_is_terminal {
+            return cat_highlighted(
+                &args.file,
+                &args.highlight.language,
+                &args.highlight.theme,
+                args.show_all,
+                args.list_themes,
+                args.number_lines,
+                streams,
+            );
+        }
+
+        cat_command(
+            &args.file,
+            &mut streams.stdin,
+            &mut streams.stdout,
+            &mut streams.stderr,
+        )
+    }
+}
+
+/// Copy `files`, or `input` if there are none, to `out` as they are. Files that
+/// can't be read are reported to `err` and skipped, returning 1 once the rest have
+/// been copied.
+pub fn cat_command(
+    files: &[OsString],
+    input: &mut impl Read,
+    out: &mut impl Write,
+    err: &mut impl Write,
+) -> Result<i32> {
+    if reads_stdin(files) {
+        io::copy(input, out)?;
+        return Ok(0);
+    }
+
+    let mut status = 0;
+    for file in files {
+        if file == "-" {
+            io::copy(input, out)?;
+            continue;
+        }
+        match File::open(file).and_then(|mut f| io::copy(&mut f, out)) {
+            Ok(_) => {}
+            // there's no point in going on once the output is gone
+            Err(e) if error::is_write_error(&e) => return Err(e.into()),
+            Err(e) => {
+                writeln!(err, "cat: {}", Error::io(Path::new(file).display(), &e))?;
+                status = error::status::FAILURE;
+            }
+        }
+    }
+    Ok(status)
+}
+
+/// Print `files`, or stdin if there are none, through bat
+#[cfg(feature = "highlight")]
+pub fn cat_highlighted(
+    files: &[OsString],
+    language: &str,
+    theme: &str,
+    show_all: bool,
+    list_themes: bool,
+    number_lines: bool,
+    streams: &mut Streams,
+) -> Result<i32> {
+    if list_themes {
+        for theme in PrettyPrinter::new().themes() {
+            writeln!(streams.stdout, "{theme}")?;
+        }
+        return Ok(0);
+    }
+
+    let mut pretty_printer = PrettyPrinter::new();
+    pretty_printer
+        .language(language)
+        .theme(theme)
+        .colored_output(streams.stdout_is_terminal)
+        .show_nonprintable(show_all)
+        .line_numbers(number_lines);
+
+    let mut status = 0;
+    if reads_stdin(files) {
+        pretty_printer.input_from_reader(&mut streams.stdin);
+    } else {
+        // bat gives up on all of its inputs if one can't be read, so leave those out
+        let mut readable = Vec::with_capacity(files.len());
+        for file in files {
+            match readable_file(file) {
+                Ok(()) => readable.push(file),
+                Err(e) => {
+                    streams.report("cat", &Error::io(Path::new(file).display(), &e))?;
+                    status = error::status::FAILURE;
+                }
+            }
+        }
+        if readable.is_empty() {
+            return Ok(status);
+        }
+        pretty_printer.input_files(readable);
+    }
+    pretty_print(&mut pretty_printer, &mut streams.stdout)?;
+    Ok(status)
+}
+
+/// Whether `file` is one that can be opened and read as a whole
+#[cfg(feature = "highlight")]
+fn readable_file(file: &OsStr) -> io::Result<()> {
+    if file == "-" {
+        return Ok(());
+    }
+    if File::open(file)?.metadata()?.is_dir() {
+        return Err(io::Error::from(io::ErrorKind::IsADirectory));
+    }
+    Ok(())
+}
+
+fn reads_stdin(files: &[OsString]) -> bool {
+    files.is_empty() || files.iter().all(|file| file == "-")
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_cmd::{Command, cargo::cargo_bin, prelude::*};
+    use core::str;
+
+    #[allow(unused_imports)]
+    use crate::*;
+
+    const FILE_TO_CAT: &str = "/etc/hosts";
+
+    #[test]
+    fn success() {
+        // Arrange
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.arg("cat");
+        cmd.arg(FILE_TO_CAT);
+
+        // Assert
+        cmd.assert().success();
+        cmd.assert().stdout(predicates::str::contains("127.0.0.1"));
+    }
+
+    #[test]
+    fn from_stdin() {
+        // Arrange
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.arg("cat");
+        cmd.write_stdin("woah hey there");
+
+        // Assert
+        cmd.assert().success();
+        cmd.assert()
+            .stdout(predicates::str::contains("woah hey there"));
+    }
+
+    #[test]
+    fn continues_past_missing_files() {
+        // Arrange
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.args(["cat", "/nonexistent", FILE_TO_CAT]);
+
+        // Assert
+        cmd.assert().code(1);
+        cmd.assert()
+            .stdout(predicates::str::contains("127.0.0.1"))
+            .stderr("cat: /nonexistent: No such file or directory\n");
+    }
+
+    #[test]
+    fn stops_at_closed_pipe() {
+        // Arrange
+        let mut cmd = std::process::Command::new(cargo_bin(env!("CARGO_PKG_NAME")));
+
+        // Act
+        cmd.args(["cat", FILE_TO_CAT, "/nonexistent"])
+            .stdout(closed_pipe());
+
+        // Assert
+        cmd.assert().code(error::status::BROKEN_PIPE).stderr("");
+    }
+
+    #[test]
+    fn concatenates_in_process() {
+        // Arrange
+        let files = ["-".into(), "/nonexistent".into()];
+        let (mut out, mut err) = (Vec::new(), Vec::new());
+
+        // Act
+        let status = super::cat_command(&files, &mut &b"henlo\n"[..], &mut out, &mut err).unwrap();
+
+        // Assert
+        assert_eq!(status, 1);
+        assert_eq!(out, b"henlo\n");
+        assert_eq!(err, b"cat: /nonexistent: No such file or directory\n");
+    }
+}
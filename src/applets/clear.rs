@@ -0,0 +1,55 @@
+use std::io::Write;
+
+use anyhow::Result;
+use clap::{ArgMatches, Command, CommandFactory, Parser};
+
+use super::Streams;
+use crate::applet::Applet;
+
+/// Clear the terminal screen
+#[derive(Parser)]
+pub struct ClearArgs {}
+
+pub struct Clear;
+
+impl Applet for Clear {
+    fn name(&self) -> &'static str {
+        "clear"
+    }
+
+    fn command(&self) -> Command {
+        ClearArgs::command()
+    }
+
+    fn run(&self, _matches: &ArgMatches, streams: &mut Streams) -> Result<i32> {
+        clear_command(&mut streams.stdout)?;
+        Ok(0)
+    }
+}
+
+pub fn clear_command(out: &mut impl Write) -> Result<()> {
+    writeln!(out, "\x1b[2J\x1b[H")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_cmd::Command;
+
+    #[allow(unused_imports)]
+    use crate::*;
+
+    #[test]
+    fn success() {
+        // Arrange
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.arg("clear");
+
+        // Assert
+        cmd.assert().success();
+        // TODO: is there a better way to test this?
+        cmd.assert().stdout("\u{1b}[2J\u{1b}[H\n");
+    }
+}
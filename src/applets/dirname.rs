@@ -0,0 +1,79 @@
+use std::{
+    ffi::{OsStr, OsString},
+    io::Write,
+    os::unix::ffi::OsStrExt,
+};
+
+use anyhow::Result;
+use clap::{ArgMatches, Command, CommandFactory, FromArgMatches, Parser};
+
+use super::Streams;
+use crate::applet::Applet;
+
+/// Output each NAME with its last non-slash component and trailing slashes
+/// removed; if NAME contains no /'s, output '.' (meaning the current directory).
+#[derive(Parser)]
+#[command(verbatim_doc_comment)]
+pub struct DirnameArgs {
+    #[arg(required = true)]
+    pub name: Vec<OsString>,
+
+    /// end each output line with NUL, not newline
+    #[arg(long, short, visible_short_alias = '0')]
+    pub zero: bool,
+}
+
+pub struct Dirname;
+
+impl Applet for Dirname {
+    fn name(&self) -> &'static str {
+        "dirname"
+    }
+
+    fn command(&self) -> Command {
+        DirnameArgs::command()
+    }
+
+    fn run(&self, matches: &ArgMatches, streams: &mut Streams) -> Result<i32> {
+        let args = DirnameArgs::from_arg_matches(matches)?;
+        dirname_command(&args, &mut streams.stdout)?;
+        Ok(0)
+    }
+}
+
+pub fn dirname_command(args: &DirnameArgs, out: &mut impl Write) -> Result<()> {
+    for name in &args.name {
+        out.write_all(dirname(name).as_bytes())?;
+        out.write_all(if args.zero { b"\0" } else { b"\n" })?;
+    }
+    Ok(())
+}
+
+/// `name` with its last component removed, or `.` if it has no directory components
+pub fn dirname(name: &OsStr) -> &OsStr {
+    let bytes = name.as_bytes();
+    bytes
+        .iter()
+        .rposition(|&byte| byte == b'/')
+        .map_or(OsStr::new("."), |slash| OsStr::from_bytes(&bytes[..slash]))
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_cmd::{cargo::cargo_bin, prelude::*};
+
+    #[test]
+    fn stops_at_closed_pipe() {
+        // Arrange
+        let mut cmd = std::process::Command::new(cargo_bin(env!("CARGO_PKG_NAME")));
+
+        // Act
+        cmd.args(["dirname", "/usr/lib"])
+            .stdout(crate::closed_pipe());
+
+        // Assert
+        cmd.assert()
+            .code(crate::error::status::BROKEN_PIPE)
+            .stderr("");
+    }
+}
@@ -0,0 +1,307 @@
+use std::{ffi::OsString, os::unix::ffi::OsStrExt};
+
+use anyhow::Result;
+use clap::{ArgMatches, Command, CommandFactory, FromArgMatches, Parser};
+
+use super::{HighlightArgs, Streams};
+use crate::applet::Applet;
+
+/// Write arguments to standard output
+#[derive(Parser)]
+pub struct EchoArgs {
+    /// disable interpretation of backslash escapes
+    #[arg(short = 'E', default_value_t = true)]
+    pub disable_backslash_escapes: bool,
+
+    /// enable interpretation of backslash escapes
+    #[arg(short = 'e', default_value_t = false)]
+    pub enable_backslash_escapes: bool,
+
+    #[command(flatten)]
+    pub highlight: HighlightArgs,
+
+    /// do not output a trailing newline
+    #[arg(long, short, default_value_t = false)]
+    pub nonewline: bool,
+
+    #[arg(default_value = "")]
+    pub string: Vec<OsString>,
+}
+
+pub struct Echo;
+
+impl Applet for Echo {
+    fn name(&self) -> &'static str {
+        "echo"
+    }
+
+    fn command(&self) -> Command {
+        EchoArgs::command()
+    }
+
+    #[cfg(feature = "highlight")]
+    fn complete(&self, command: Command) -> Command {
+        super::complete_highlight_args(command)
+    }
+
+    fn run(&self, matches: &ArgMatches, streams: &mut Streams) -> Result<i32> {
+        let args = EchoArgs::from_arg_matches(matches)?;
+        echo_command(&args, streams)?;
+        Ok(0)
+    }
+}
+
+pub fn echo_command(args: &EchoArgs, streams: &mut Streams) -> Result<()> {
+    args.highlight.write(&echo(args), streams)
+}
+
+/// The escape sequences `echo` interprets, in the order they are replaced
+const ESCAPES: [(&[u8], &[u8]); 11] = [
+    (b"\\\\", b"\\"),
+    (b"\\a", b"\x07"),
+    (b"\\b", b"\x08"),
+    (b"\\c", b""),
+    (b"\\e", b"\x1B"),
+    (b"\\f", b"\x0C"),
+    (b"\\n", b"\n"),
+    (b"\\r", b"\r"),
+    (b"\\t", b"\t"),
+    (b"\\v", b"\x0B"),
+    (b"\\0", b"\0"),
+];
+
+/// The bytes `echo` prints for `args`; arguments that aren't valid UTF-8 are
+/// printed as they are
+pub fn echo(args: &EchoArgs) -> Vec<u8> {
+    let words: Vec<&[u8]> = args.string.iter().map(|word| word.as_bytes()).collect();
+    let mut text = words.join(&b' ');
+    if !args.nonewline {
+        text.push(b'\n');
+    }
+
+    if args.disable_backslash_escapes || !args.enable_backslash_escapes {
+        for (escape, byte) in ESCAPES {
+            text = replace(&text, escape, byte);
+        }
+
+        let mut result = Vec::with_capacity(text.len());
+        let mut bytes = text.iter().copied().peekable();
+
+        while let Some(b) = bytes.next() {
+            if b == b'\\' {
+                if let Some(b'x') = bytes.peek() {
+                    bytes.next(); // consume 'x'
+                    let hex: Vec<u8> = bytes.by_ref().take(2).collect();
+                    match std::str::from_utf8(&hex)
+                        .ok()
+                        .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                    {
+                        Some(byte) => result.push(byte),
+                        None => {
+                            result.extend_from_slice(b"\\x");
+                            result.extend_from_slice(&hex);
+                        }
+                    }
+                } else {
+                    result.push(b);
+                }
+            } else {
+                result.push(b);
+            }
+        }
+        text = result;
+    }
+    text
+}
+
+/// `text` with every occurrence of `from` replaced by `to`
+fn replace(text: &[u8], from: &[u8], to: &[u8]) -> Vec<u8> {
+    let mut result = Vec::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(&first) = rest.first() {
+        if rest.starts_with(from) {
+            result.extend_from_slice(to);
+            rest = &rest[from.len()..];
+        } else {
+            result.push(first);
+            rest = &rest[1..];
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    #![expect(non_snake_case)]
+
+    use assert_cmd::{Command, cargo::cargo_bin, prelude::*};
+    use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+    #[allow(unused_imports)]
+    use crate::*;
+
+    // TODO: add tests for --language and --theme args
+
+    #[test]
+    fn success() {
+        // Arrange
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.arg("echo");
+
+        // Assert
+        cmd.assert().success();
+        cmd.assert().stdout("\n");
+    }
+
+    #[test]
+    fn with_text_is_success() {
+        // Arrange
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.arg("echo");
+        cmd.arg("henlo");
+
+        // Assert
+        cmd.assert().success();
+        cmd.assert().stdout("henlo\n");
+    }
+
+    #[test]
+    fn with_multiple_text_is_success() {
+        // Arrange
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.arg("echo");
+        cmd.arg("henlo");
+        cmd.arg("world");
+
+        // Assert
+        cmd.assert().success();
+        cmd.assert().stdout("henlo world\n");
+    }
+
+    #[test]
+    fn with_no_args_does_not_interpret_backslash_chars_by_default() {
+        // Arrange
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.arg("echo");
+        cmd.arg("\r");
+
+        // Assert
+        cmd.assert().success();
+        cmd.assert().stdout("\r\n");
+    }
+
+    #[test]
+    fn with__E_disables_interpretation_of_backslash_chars() {
+        // Arrange
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.arg("echo");
+        cmd.arg("-E");
+        cmd.arg("\r");
+
+        // Assert
+        cmd.assert().success();
+        cmd.assert().stdout("\r\n");
+    }
+
+    #[test]
+    fn with__e_enables_interpretation_of_backslash_chars() {
+        // Arrange
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+        let c_hello_world = "\\x23\\x69\\x6e\\x63\\x6c\\x75\\x64\\x65\\x20\\x3c\\x73\\x74\\x64\\x69\\x6f\\x2e\\x68\\x3e\\x0a\\x0a\\x69\\x6e\\x74\\x20\\x6d\\x61\\x69\\x6e\\x28\\x29\\x20\\x7b\\x0a\\x20\\x20\\x20\\x20\\x70\\x72\\x69\\x6e\\x74\\x66\\x28\\x22\\x68\\x65\\x6c\\x6c\\x6f\\x2c\\x20\\x77\\x6f\\x72\\x6c\\x64\\x5c\\x6e\\x22\\x29\\x3b\\x0a\\x7d";
+
+        // Act
+        cmd.arg("echo");
+        cmd.arg("-e");
+        cmd.arg(c_hello_world);
+
+        // Assert
+        cmd.assert().success();
+        cmd.assert()
+            .stdout("#include <stdio.h>\n\nint main() {\n    printf(\"hello, world\\n\");\n}\n");
+    }
+
+    #[test]
+    fn with___nonewline_does_not_output_a_newline() {
+        // Arrange
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.arg("echo");
+        cmd.arg("--nonewline");
+        cmd.arg("howdy");
+
+        // Assert
+        cmd.assert().success();
+        cmd.assert().stdout("howdy");
+        // TODO: figure out how to assert the absence of a newline
+    }
+
+    #[test]
+    fn with_all_args() {
+        // Arrange
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.arg("echo");
+        cmd.arg("-e");
+        cmd.arg("-n"); // --nonewline
+        cmd.arg("weirdoutput");
+
+        // Assert
+        cmd.assert().success();
+        cmd.assert().stdout("weirdoutput");
+    }
+
+    #[test]
+    fn stops_at_closed_pipe() {
+        // Arrange
+        let mut cmd = std::process::Command::new(cargo_bin(env!("CARGO_PKG_NAME")));
+
+        // Act
+        cmd.args(["echo", "nobody is listening"])
+            .stdout(closed_pipe());
+
+        // Assert
+        cmd.assert().code(error::status::BROKEN_PIPE).stderr("");
+    }
+
+    #[test]
+    fn reports_write_errors() {
+        // Arrange
+        let mut cmd = std::process::Command::new(cargo_bin(env!("CARGO_PKG_NAME")));
+
+        // Act
+        cmd.args(["echo", "no room"])
+            .stdout(std::fs::File::create("/dev/full").unwrap());
+
+        // Assert
+        cmd.assert()
+            .code(1)
+            .stderr("echo: write error: No space left on device\n");
+    }
+
+    #[test]
+    fn prints_bytes_as_they_are() {
+        // Arrange
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.arg("echo");
+        cmd.arg(OsStr::from_bytes(b"caf\xe9"));
+        cmd.arg("\\xe9");
+
+        // Assert
+        cmd.assert().success();
+        cmd.assert().stdout(&b"caf\xe9 \xe9\n"[..]);
+    }
+}
@@ -0,0 +1,323 @@
+use anyhow::{Result, bail};
+#[cfg(feature = "highlight")]
+use bat::PrettyPrinter;
+use clap::{ArgAction, ArgMatches, Command as ClapCommand, CommandFactory, FromArgMatches, Parser};
+use std::{
+    collections::BTreeMap,
+    env::{remove_var, set_current_dir, vars_os},
+    ffi::OsString,
+    fmt::Display,
+    io::{self, Write},
+    os::unix::{ffi::OsStrExt, fs::symlink, process::ExitStatusExt},
+    path::PathBuf,
+    process::Command,
+};
+
+#[cfg(feature = "highlight")]
+use super::pretty_print;
+use super::{Streams, which::which_command, write_json};
+use crate::{
+    applet::Applet,
+    error::{Error, status},
+    parse_kv_pair,
+};
+
+/// Run a program in a modified environment
+#[derive(Parser)]
+pub struct EnvArgs {
+    /// pass ARG as the zeroth argument of COMMAND
+    #[arg(long, short)]
+    pub argv0: Option<OsString>,
+
+    /// change working directory to DIR
+    #[arg(long, short)]
+    pub chdir: Option<PathBuf>,
+
+    /// start with an empty environment
+    #[arg(long, short = 'i')]
+    pub ignore_environment: bool,
+
+    /// end echo output line with NUL, not newline
+    #[arg(long, short = '0', visible_alias = "zero", visible_short_alias = '0')]
+    pub null: bool,
+
+    /// print the environment as a JSON object, for scripts
+    #[arg(long, conflicts_with = "command")]
+    pub json: bool,
+
+    /// remove variable from the environment
+    #[arg(action = ArgAction::Append, long, short)]
+    pub unset: Vec<OsString>,
+
+    /// KEY=VALUE to set in the environment
+    #[arg(value_parser = parse_kv_pair)]
+    pub kv_pair: Vec<String>,
+
+    /// command to run in the environment
+    #[arg(last = true)]
+    pub command: Vec<OsString>,
+    // FIXME: `last` requires the command to be passed via `--`, which differs from coreutils env
+}
+
+pub struct Env;
+
+impl Applet for Env {
+    fn name(&self) -> &'static str {
+        "env"
+    }
+
+    /// `--chdir` and `--unset` change the working directory and environment of the process
+    fn in_process(&self) -> bool {
+        false
+    }
+
+    fn command(&self) -> ClapCommand {
+        EnvArgs::command()
+    }
+
+    fn usage_status(&self) -> i32 {
+        status::CANNOT_RUN
+    }
+
+    fn run(&self, matches: &ArgMatches, streams: &mut Streams) -> Result<i32> {
+        let args = EnvArgs::from_arg_matches(matches)?;
+        env_command(&args, streams)
+    }
+}
+
+#[derive(Debug)]
+struct KVPair<'a> {
+    key: &'a str,
+    value: &'a str,
+}
+
+impl<'a> KVPair<'a> {
+    fn parse(line: &'a str) -> Result<Self> {
+        if let Some((k, v)) = line.split_once('=') {
+            Ok(Self { key: k, value: v })
+        } else {
+            bail!("failed to parse key-value pair in line: {line}")
+        }
+    }
+}
+
+impl Display for KVPair<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}={}", self.key, self.value)
+    }
+}
+
+pub fn env_command(args: &EnvArgs, streams: &mut Streams) -> Result<i32> {
+    let EnvArgs {
+        argv0,
+        chdir,
+        ignore_environment,
+        null,
+        json,
+        unset,
+        kv_pair,
+        command,
+    } = args;
+    let line_ending = if *null { "" } else { "\n" };
+
+    if let Some(dir) = chdir {
+        set_current_dir(dir).map_err(|e| {
+            Error::io(
+                format!("cannot change directory to '{}'", dir.display()),
+                &e,
+            )
+            .with_status(status::CANNOT_RUN)
+        })?;
+    }
+
+    if cfg!(debug_assertions) {
+        eprintln!("command:            {command:?}");
+        eprintln!("argv0:              {argv0:?}");
+        eprintln!("chdir:              {chdir:?}");
+        eprintln!("ignore_environment: {ignore_environment:?}");
+        eprintln!("null:               {null:?}");
+        eprintln!("unset:              {unset:?}");
+        eprintln!("kv_pair:            {kv_pair:?}");
+    }
+
+    if let Some((cmd_path, cmd_args)) = command.split_first() {
+        let mut program = PathBuf::from(cmd_path);
+
+        if let Some(arg) = argv0 {
+            let temp_dir = std::env::temp_dir();
+            let symlink_path = temp_dir.join(arg);
+
+            let cmd_path_abs = match which_command(false, cmd_path, true, &mut io::sink()) {
+                Ok(Some(path)) => path,
+                Ok(None) => {
+                    let e = io::Error::from(io::ErrorKind::NotFound);
+                    return Err(Error::io(format!("'{}'", program.display()), &e)
+                        .with_status(status::NOT_FOUND)
+                        .into());
+                }
+                Err(e) => return Err(Error::new(e).with_status(status::CANNOT_RUN).into()),
+            };
+
+            let link_error = |e: io::Error| {
+                let target = format!("failed to create symlink '{}'", symlink_path.display());
+                Error::io(target, &e).with_status(status::CANNOT_RUN)
+            };
+            if symlink_path.exists() {
+                std::fs::remove_file(&symlink_path).map_err(link_error)?;
+            }
+            symlink(&cmd_path_abs, &symlink_path).map_err(link_error)?;
+
+            program = symlink_path;
+        }
+
+        for key in unset {
+            unsafe { remove_var(key) };
+        }
+
+        let mut command = Command::new(&program);
+        command.args(cmd_args);
+
+        if *ignore_environment {
+            command.env_clear();
+        }
+
+        for line in kv_pair {
+            let kv_pair =
+                KVPair::parse(line).map_err(|e| Error::new(e).with_status(status::CANNOT_RUN))?;
+            command.env(kv_pair.key, kv_pair.value);
+        }
+
+        return match command.status() {
+            // like a shell, report death by a signal as 128 plus its number
+            Ok(exit) => Ok(exit
+                .code()
+                .or_else(|| exit.signal().map(|signal| 128 + signal))
+                .unwrap_or(status::FAILURE)),
+            Err(e) => {
+                let status = if e.kind() == io::ErrorKind::NotFound {
+                    status::NOT_FOUND
+                } else {
+                    status::CANNOT_INVOKE
+                };
+                Err(Error::io(format!("'{}'", program.display()), &e)
+                    .with_status(status)
+                    .into())
+            }
+        };
+    }
+
+    if *json {
+        let vars: BTreeMap<String, String> = vars_os()
+            .map(|(key, value)| {
+                (
+                    key.to_string_lossy().into_owned(),
+                    value.to_string_lossy().into_owned(),
+                )
+            })
+            .collect();
+        write_json(&vars, &mut streams.stdout)?;
+        return Ok(0);
+    }
+
+    // variables are printed byte for byte, whether or not they are valid UTF-8
+    let mut kv_pairs = Vec::new();
+    for (key, value) in vars_os() {
+        kv_pairs.extend_from_slice(key.as_bytes());
+        kv_pairs.push(b'=');
+        kv_pairs.extend_from_slice(value.as_bytes());
+        kv_pairs.extend_from_slice(line_ending.as_bytes());
+    }
+
+    #[cfg(feature = "highlight")]
+    if streams.stdout_is_terminal {
+        let mut pretty_printer = PrettyPrinter::new();
+        pretty_printer.input_from_bytes(&kv_pairs).language("env");
+        pretty_print(&mut pretty_printer, &mut streams.stdout)?;
+        return Ok(0);
+    }
+    streams.stdout.write_all(&kv_pairs)?;
+    Ok(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_cmd::{Command, cargo::cargo_bin, prelude::*};
+    use predicates::prelude::*;
+
+    #[allow(unused_imports)]
+    use crate::*;
+
+    #[test]
+    fn success() {
+        // Arrange
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.arg("env");
+
+        // Assert
+        cmd.assert().success();
+        // TODO: make a better test
+        cmd.assert().stdout(predicates::str::contains("="));
+    }
+
+    #[test]
+    fn exit_statuses() {
+        for (args, status, message) in [
+            (&["--", "sh", "-c", "exit 3"][..], 3, None),
+            (
+                &["--chdir", "/nonexistent", "--", "true"],
+                125,
+                Some("env: cannot change directory to '/nonexistent': No such file or directory\n"),
+            ),
+            (&["--", "/etc/hosts"], 126, None),
+            (
+                &["--", "rizzybox-no-such-command"],
+                127,
+                Some("env: 'rizzybox-no-such-command': No such file or directory\n"),
+            ),
+            (&["--no-such-flag"], 125, None),
+        ] {
+            // Arrange
+            let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+            // Act
+            cmd.arg("env").args(args);
+
+            // Assert
+            let assert = cmd.assert().code(status);
+            if let Some(message) = message {
+                assert.stderr(predicates::str::ends_with(message));
+            }
+        }
+    }
+
+    #[test]
+    fn stops_at_closed_pipe() {
+        // Arrange
+        let mut cmd = std::process::Command::new(cargo_bin(env!("CARGO_PKG_NAME")));
+
+        // Act
+        cmd.arg("env").stdout(closed_pipe());
+
+        // Assert
+        cmd.assert()
+            .code(error::status::BROKEN_PIPE)
+            .stderr(predicates::str::contains("Broken pipe").not());
+    }
+
+    #[test]
+    fn json_is_an_object_of_variables() {
+        // Arrange
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.args(["env", "--json"])
+            .env("RIZZYBOX_JSON_TEST", "a \"quoted\" value");
+
+        // Assert
+        let output = cmd.assert().success().get_output().stdout.clone();
+        let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(json["RIZZYBOX_JSON_TEST"], "a \"quoted\" value");
+    }
+}
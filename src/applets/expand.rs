@@ -0,0 +1,138 @@
+use std::{
+    io::{Read, Write},
+    path::PathBuf,
+};
+
+use anyhow::Result;
+use clap::{ArgMatches, Command, CommandFactory, FromArgMatches, Parser};
+
+use super::Streams;
+use crate::{applet::Applet, error::Error};
+
+///  Convert tabs in each FILE to spaces, writing to standard output
+#[derive(Parser)]
+pub struct ExpandArgs {
+    /// file to concatenate
+    #[arg(default_value = "-")]
+    pub file: PathBuf,
+
+    /// have tabs N characters apart, not 8
+    #[arg(long, short, value_name = "N,LIST", value_delimiter = ',', num_args = 1..)]
+    pub tabs: Vec<String>,
+}
+
+pub struct Expand;
+
+impl Applet for Expand {
+    fn name(&self) -> &'static str {
+        "expand"
+    }
+
+    fn command(&self) -> Command {
+        ExpandArgs::command()
+    }
+
+    fn run(&self, matches: &ArgMatches, streams: &mut Streams) -> Result<i32> {
+        let args = ExpandArgs::from_arg_matches(matches)?;
+        expand_command(&args, &mut streams.stdin, &mut streams.stdout)?;
+        Ok(0)
+    }
+}
+
+/// Expand FILE, or `input` if it is `-`, to `out`
+pub fn expand_command(
+    args: &ExpandArgs,
+    input: &mut impl Read,
+    out: &mut impl Write,
+) -> Result<()> {
+    let file = &args.file;
+    let content = if file.as_os_str() == "-" {
+        let mut content = Vec::new();
+        input.read_to_end(&mut content)?;
+        content
+    } else {
+        std::fs::read(file).map_err(|e| Error::io(file.display(), &e))?
+    };
+    out.write_all(&expand(&content, &args.tabs))?;
+    out.write_all(b"\n")?;
+    Ok(())
+}
+
+/// `content` with its tabs replaced by as many spaces as the `tabs` list adds up to
+pub fn expand(content: &[u8], tabs: &[String]) -> Vec<u8> {
+    let mut total_spaces = 0;
+    for tab in tabs {
+        let repeated = tab.parse::<usize>();
+        if let Ok(repeated) = repeated {
+            total_spaces += repeated;
+        } else {
+            break; // TODO: handle '+' and '/' cases
+        }
+    }
+
+    let mut expanded = Vec::with_capacity(content.len());
+    for &byte in content {
+        if byte == b'\t' {
+            expanded.resize(expanded.len() + total_spaces, b' ');
+        } else {
+            expanded.push(byte);
+        }
+    }
+    expanded
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_cmd::{Command, cargo::cargo_bin, prelude::*};
+    use std::{env, ffi::OsStr, fs, os::unix::ffi::OsStrExt};
+
+    use super::{ExpandArgs, expand_command};
+
+    #[test]
+    fn expands_tabs_in_process() {
+        // Arrange
+        let args = ExpandArgs {
+            file: "-".into(),
+            tabs: vec!["2".to_owned()],
+        };
+        let mut out = Vec::new();
+
+        // Act
+        expand_command(&args, &mut &b"a\tb"[..], &mut out).unwrap();
+
+        // Assert
+        assert_eq!(out, b"a  b\n");
+    }
+
+    #[test]
+    fn keeps_invalid_utf8_names_and_content() {
+        // Arrange
+        let dir = env::temp_dir().join(format!("rizzybox-expand-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let file = dir.join(OsStr::from_bytes(b"caf\xe9.txt"));
+        fs::write(&file, b"caf\xe9\t1").unwrap();
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.args(["expand", "--tabs=1"]).arg(&file);
+
+        // Assert
+        cmd.assert().success().stdout(&b"caf\xe9 1\n"[..]);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn stops_at_closed_pipe() {
+        // Arrange
+        let mut cmd = std::process::Command::new(cargo_bin(env!("CARGO_PKG_NAME")));
+
+        // Act
+        cmd.args(["expand", concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml")])
+            .stdout(crate::closed_pipe());
+
+        // Assert
+        cmd.assert()
+            .code(crate::error::status::BROKEN_PIPE)
+            .stderr("");
+    }
+}
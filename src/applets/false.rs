@@ -0,0 +1,29 @@
+use anyhow::Result;
+use clap::{ArgMatches, Command, CommandFactory, Parser};
+
+use super::Streams;
+use crate::applet::Applet;
+
+/// Do nothing and exit with a failure status
+#[derive(Parser)]
+pub struct FalseArgs {}
+
+pub struct False;
+
+impl Applet for False {
+    fn name(&self) -> &'static str {
+        "false"
+    }
+
+    fn command(&self) -> Command {
+        FalseArgs::command()
+    }
+
+    fn run(&self, _matches: &ArgMatches, _streams: &mut Streams) -> Result<i32> {
+        Ok(false_command())
+    }
+}
+
+pub fn false_command() -> i32 {
+    1
+}
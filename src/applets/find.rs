@@ -0,0 +1,1050 @@
+use std::{
+    cmp::Ordering,
+    ffi::{OsStr, OsString},
+    fs::{self, Metadata},
+    io::{self, Write},
+    os::unix::{
+        ffi::{OsStrExt, OsStringExt},
+        fs::{FileTypeExt, MetadataExt},
+    },
+    path::Path,
+    process::Command,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Result;
+use clap::{ArgMatches, Command as ClapCommand, CommandFactory, FromArgMatches, Parser};
+
+use super::{Streams, fnmatch};
+use crate::{
+    applet::Applet,
+    error::{Error, status},
+};
+
+/// How many bytes of paths `-exec ... {} +` gathers before running its command
+const EXEC_BATCH_BYTES: usize = 64 * 1024;
+
+/// Search for files in a directory hierarchy
+#[derive(Parser)]
+#[command(
+    override_usage = "find [-H] [-L] [-P] [PATH]... [EXPRESSION]",
+    after_help = "\
+Expression, evaluated for every file under each PATH (`.` by default):
+  tests    -name GLOB  -iname GLOB  -path GLOB  -type [fdlpsbc]  -size [+-]N[cwbkMG]
+           -mtime [+-]N  -mmin [+-]N  -newer FILE  -perm [-/]MODE  -user NAME
+           -true  -false
+  options  -maxdepth N  -mindepth N  -depth
+  actions  -print  -print0  -delete  -exec COMMAND ;  -exec COMMAND {} +  -prune
+  operators  ( EXPR )  ! EXPR  EXPR [-a] EXPR  EXPR -o EXPR
+Without an action other than -prune, the paths the expression is true for are printed."
+)]
+pub struct FindArgs {
+    /// never follow symlinks (the default)
+    #[arg(short = 'P', overrides_with_all = ["follow_paths", "follow"])]
+    pub physical: bool,
+
+    /// follow symlinks given as PATH, but not those found under them
+    #[arg(short = 'H', overrides_with_all = ["physical", "follow"])]
+    pub follow_paths: bool,
+
+    /// follow all symlinks
+    #[arg(short = 'L', overrides_with_all = ["physical", "follow_paths"])]
+    pub follow: bool,
+
+    /// the PATHs to search, followed by the EXPRESSION
+    #[arg(
+        trailing_var_arg = true,
+        allow_hyphen_values = true,
+        value_name = "PATH|EXPRESSION"
+    )]
+    pub args: Vec<OsString>,
+}
+
+pub struct Find;
+
+impl Applet for Find {
+    fn name(&self) -> &'static str {
+        "find"
+    }
+
+    /// commands run by `-exec` write to the process' own stdout, not the applet's
+    fn in_process(&self) -> bool {
+        false
+    }
+
+    fn command(&self) -> ClapCommand {
+        FindArgs::command()
+    }
+
+    fn run(&self, matches: &ArgMatches, streams: &mut Streams) -> Result<i32> {
+        let args = FindArgs::from_arg_matches(matches)?;
+        find_command(&args, &mut streams.stdout, &mut streams.stderr)
+    }
+}
+
+/// Walk the paths in `args`, evaluating its expression for each file and printing
+/// to `out`. Files that can't be read are reported to `err` and the walk carries
+/// on, returning 1 at the end.
+pub fn find_command(args: &FindArgs, out: &mut dyn Write, err: &mut dyn Write) -> Result<i32> {
+    let first_expression = args
+        .args
+        .iter()
+        .position(|arg| arg.as_bytes().starts_with(b"-") || arg == "!" || arg == "(")
+        .unwrap_or(args.args.len());
+    let (paths, expression) = args.args.split_at(first_expression);
+    let (mut expr, options) = parse_expression(expression)?;
+
+    let mut walk = Walk {
+        options,
+        follow: if args.follow {
+            Follow::Always
+        } else if args.follow_paths {
+            Follow::Paths
+        } else {
+            Follow::Never
+        },
+        now: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |now| now.as_secs() as i64),
+        prune: false,
+        failed: false,
+        out,
+        err,
+    };
+
+    let default_path = [OsString::from(".")];
+    let paths = if paths.is_empty() {
+        &default_path[..]
+    } else {
+        paths
+    };
+    for path in paths {
+        walk.visit(&mut expr, Path::new(path), 0, &mut Vec::new())?;
+    }
+    expr.flush(&mut walk)?;
+
+    Ok(if walk.failed { status::FAILURE } else { 0 })
+}
+
+/// Options that apply to the whole walk, wherever they are in the expression
+#[derive(Debug, Default, PartialEq)]
+pub struct Options {
+    pub max_depth: Option<usize>,
+    pub min_depth: usize,
+    /// whether a directory is evaluated after what is in it, which `-delete` needs
+    pub depth_first: bool,
+}
+
+/// A node of the expression
+#[derive(Debug, PartialEq)]
+pub enum Expr {
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    True,
+    False,
+    Name {
+        glob: Vec<u8>,
+        ignore_case: bool,
+    },
+    Path(Vec<u8>),
+    /// the letters of the types to match, as `-type` takes them
+    Type(Vec<u8>),
+    /// the size, in units of the given number of bytes, rounded up
+    Size(Comparison, u64),
+    /// the time since the last modification, in units of the given number of seconds
+    Age(Comparison, i64),
+    /// modified after the given seconds and nanoseconds since the epoch
+    Newer(i64, i64),
+    Perm(PermMatch, u32),
+    User(u32),
+    Print,
+    Print0,
+    Delete,
+    Exec(Exec),
+    Prune,
+}
+
+/// `N`, `+N` or `-N`: exactly, more than, or less than N
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Comparison {
+    pub ordering: Ordering,
+    pub n: u64,
+}
+
+impl Comparison {
+    fn parse(arg: &str) -> Option<Self> {
+        let (ordering, n) = match arg.as_bytes().first() {
+            Some(b'+') => (Ordering::Greater, &arg[1..]),
+            Some(b'-') => (Ordering::Less, &arg[1..]),
+            _ => (Ordering::Equal, arg),
+        };
+        Some(Self {
+            ordering,
+            n: n.parse().ok()?,
+        })
+    }
+
+    fn matches(self, value: i64) -> bool {
+        value.cmp(&(self.n as i64)) == self.ordering
+    }
+}
+
+/// How `-perm` compares the permission bits of a file with its mode
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum PermMatch {
+    /// `MODE`: they are exactly the mode
+    Exact,
+    /// `-MODE`: all of the mode's bits are set
+    All,
+    /// `/MODE`: any of the mode's bits is set
+    Any,
+}
+
+/// `-exec COMMAND ;`, which runs COMMAND for every file, or `-exec COMMAND {} +`,
+/// which runs it for as many at once as it can
+#[derive(Debug, PartialEq)]
+pub struct Exec {
+    pub command: Vec<OsString>,
+    /// the paths gathered for a batch, with `+`
+    pub batch: Option<Vec<OsString>>,
+}
+
+/// Split `tokens` into the expression they make up and the options among them. An
+/// expression without actions, other than `-prune`, prints what it is true for.
+pub fn parse_expression(tokens: &[OsString]) -> Result<(Expr, Options)> {
+    let mut parser = ExprParser {
+        tokens,
+        pos: 0,
+        options: Options::default(),
+        has_action: false,
+    };
+    let expr = if tokens.is_empty() {
+        Expr::True
+    } else {
+        parser.or()?
+    };
+    if let Some(token) = parser.peek() {
+        return Err(Error::new(format!("unexpected '{}'", token.display())).into());
+    }
+    let expr = if parser.has_action {
+        expr
+    } else {
+        Expr::And(Box::new(expr), Box::new(Expr::Print))
+    };
+    Ok((expr, parser.options))
+}
+
+struct ExprParser<'a> {
+    tokens: &'a [OsString],
+    pos: usize,
+    options: Options,
+    has_action: bool,
+}
+
+impl<'a> ExprParser<'a> {
+    fn peek(&self) -> Option<&'a OsStr> {
+        self.tokens.get(self.pos).map(OsString::as_os_str)
+    }
+
+    fn next_is(&mut self, names: &[&str]) -> bool {
+        let found = self
+            .peek()
+            .is_some_and(|token| names.iter().any(|name| token == *name));
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    /// The argument that follows `primary`
+    fn argument(&mut self, primary: &str) -> Result<&'a OsStr> {
+        let argument = self
+            .tokens
+            .get(self.pos)
+            .ok_or_else(|| Error::new(format!("missing argument to '{primary}'")))?;
+        self.pos += 1;
+        Ok(argument)
+    }
+
+    /// The argument that follows `primary`, which has to be UTF-8
+    fn text_argument(&mut self, primary: &str) -> Result<String> {
+        let argument = self.argument(primary)?;
+        let text = argument
+            .to_str()
+            .ok_or_else(|| invalid_argument(argument, primary))?;
+        Ok(text.to_owned())
+    }
+
+    fn or(&mut self) -> Result<Expr> {
+        let mut expr = self.and()?;
+        while self.next_is(&["-o", "-or"]) {
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    /// Operands next to each other are joined by an implicit `-a`
+    fn and(&mut self) -> Result<Expr> {
+        let mut expr = self.not()?;
+        loop {
+            let explicit = self.next_is(&["-a", "-and"]);
+            let ends = self
+                .peek()
+                .is_none_or(|token| token == "-o" || token == "-or" || token == ")");
+            if ends && !explicit {
+                return Ok(expr);
+            }
+            expr = Expr::And(Box::new(expr), Box::new(self.not()?));
+        }
+    }
+
+    fn not(&mut self) -> Result<Expr> {
+        if self.next_is(&["!", "-not"]) {
+            return Ok(Expr::Not(Box::new(self.not()?)));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Expr> {
+        let Some(token) = self.peek() else {
+            return Err(Error::new("expected an expression at the end").into());
+        };
+        self.pos += 1;
+        let Some(name) = token.to_str() else {
+            return Err(Error::new(format!("unknown predicate '{}'", token.display())).into());
+        };
+
+        let expr = match name {
+            "(" => {
+                let expr = self.or()?;
+                if !self.next_is(&[")"]) {
+                    return Err(Error::new("missing ')'").into());
+                }
+                expr
+            }
+            "-true" => Expr::True,
+            "-false" => Expr::False,
+            "-name" | "-iname" => {
+                let ignore_case = name == "-iname";
+                let mut glob = self.argument(name)?.as_bytes().to_owned();
+                if ignore_case {
+                    glob.make_ascii_lowercase();
+                }
+                Expr::Name { glob, ignore_case }
+            }
+            "-path" => Expr::Path(self.argument(name)?.as_bytes().to_owned()),
+            "-type" => {
+                let argument = self.argument(name)?;
+                let types: Vec<u8> = argument
+                    .as_bytes()
+                    .iter()
+                    .copied()
+                    .filter(|&letter| letter != b',')
+                    .collect();
+                if types.is_empty() || !types.iter().all(|letter| b"fdlpsbc".contains(letter)) {
+                    return Err(invalid_argument(argument, name).into());
+                }
+                Expr::Type(types)
+            }
+            "-size" => {
+                let argument = self.text_argument(name)?;
+                let (number, unit) = match argument.char_indices().last() {
+                    Some((i, unit)) if unit.is_ascii_alphabetic() => (&argument[..i], unit),
+                    _ => (&argument[..], 'b'),
+                };
+                let unit = match unit {
+                    'c' => 1,
+                    'w' => 2,
+                    'b' => 512,
+                    'k' => 1024,
+                    'M' => 1024 * 1024,
+                    'G' => 1024 * 1024 * 1024,
+                    _ => return Err(invalid_argument(argument.as_ref(), name).into()),
+                };
+                let comparison = Comparison::parse(number)
+                    .ok_or_else(|| invalid_argument(argument.as_ref(), name))?;
+                Expr::Size(comparison, unit)
+            }
+            "-mtime" | "-mmin" => {
+                let argument = self.text_argument(name)?;
+                let comparison = Comparison::parse(&argument)
+                    .ok_or_else(|| invalid_argument(argument.as_ref(), name))?;
+                Expr::Age(comparison, if name == "-mtime" { 24 * 60 * 60 } else { 60 })
+            }
+            "-newer" => {
+                let file = self.argument(name)?;
+                let metadata = fs::metadata(file)
+                    .map_err(|e| Error::io(format!("'{}'", file.display()), &e))?;
+                Expr::Newer(metadata.mtime(), metadata.mtime_nsec())
+            }
+            "-perm" => {
+                let argument = self.text_argument(name)?;
+                let (how, mode) = match argument.as_bytes().first() {
+                    Some(b'-') => (PermMatch::All, &argument[1..]),
+                    Some(b'/') => (PermMatch::Any, &argument[1..]),
+                    _ => (PermMatch::Exact, &argument[..]),
+                };
+                let mode =
+                    parse_mode(mode).ok_or_else(|| invalid_argument(argument.as_ref(), name))?;
+                Expr::Perm(how, mode)
+            }
+            "-user" => {
+                let argument = self.text_argument(name)?;
+                let uid = argument
+                    .parse()
+                    .ok()
+                    .or_else(|| uid_of(&argument))
+                    .ok_or_else(|| {
+                        Error::new(format!("'{argument}' is not the name of a known user"))
+                    })?;
+                Expr::User(uid)
+            }
+            "-maxdepth" | "-mindepth" => {
+                let argument = self.text_argument(name)?;
+                let depth = argument
+                    .parse()
+                    .map_err(|_| invalid_argument(argument.as_ref(), name))?;
+                if name == "-maxdepth" {
+                    self.options.max_depth = Some(depth);
+                } else {
+                    self.options.min_depth = depth;
+                }
+                Expr::True
+            }
+            "-depth" => {
+                self.options.depth_first = true;
+                Expr::True
+            }
+            "-prune" => Expr::Prune,
+            "-print" | "-print0" | "-delete" | "-exec" => {
+                self.has_action = true;
+                match name {
+                    "-print" => Expr::Print,
+                    "-print0" => Expr::Print0,
+                    "-delete" => {
+                        self.options.depth_first = true;
+                        Expr::Delete
+                    }
+                    _ => Expr::Exec(self.exec()?),
+                }
+            }
+            _ => return Err(Error::new(format!("unknown predicate '{name}'")).into()),
+        };
+        Ok(expr)
+    }
+
+    /// The command of `-exec`, up to the `;` that ends it, or the `{} +`
+    fn exec(&mut self) -> Result<Exec> {
+        let start = self.pos;
+        while let Some(token) = self.peek() {
+            self.pos += 1;
+            let command = &self.tokens[start..self.pos - 1];
+            if token == ";" && !command.is_empty() {
+                return Ok(Exec {
+                    command: command.to_vec(),
+                    batch: None,
+                });
+            }
+            if token == "+" && command.len() > 1 && command.last().is_some_and(|arg| arg == "{}") {
+                return Ok(Exec {
+                    command: command[..command.len() - 1].to_vec(),
+                    batch: Some(Vec::new()),
+                });
+            }
+        }
+        Err(Error::new("missing argument to '-exec'").into())
+    }
+}
+
+fn invalid_argument(argument: &OsStr, primary: &str) -> Error {
+    Error::new(format!(
+        "invalid argument '{}' to '{primary}'",
+        argument.display()
+    ))
+}
+
+/// Permission bits given in octal, like `755`, or symbolically, like `u+x,go=r`
+pub fn parse_mode(mode: &str) -> Option<u32> {
+    if !mode.is_empty() && mode.bytes().all(|b| (b'0'..=b'7').contains(&b)) {
+        return u32::from_str_radix(mode, 8)
+            .ok()
+            .filter(|&mode| mode <= 0o7777);
+    }
+
+    let mut bits = 0;
+    for clause in mode.split(',') {
+        let op_at = clause.find(['+', '-', '='])?;
+        let (who, rest) = clause.split_at(op_at);
+        let mut who_mask = 0;
+        for letter in who.chars() {
+            who_mask |= match letter {
+                'u' => 0o4700,
+                'g' => 0o2070,
+                'o' => 0o1007,
+                'a' => 0o7777,
+                _ => return None,
+            };
+        }
+        if who_mask == 0 {
+            who_mask = 0o7777;
+        }
+        let mut perms = 0;
+        for letter in rest[1..].chars() {
+            perms |= match letter {
+                'r' => 0o444,
+                'w' => 0o222,
+                'x' => 0o111,
+                's' => 0o6000,
+                't' => 0o1000,
+                _ => return None,
+            };
+        }
+        let perms = perms & who_mask;
+        bits = match &rest[..1] {
+            "+" => bits | perms,
+            "-" => bits & !perms,
+            _ => bits & !who_mask | perms,
+        };
+    }
+    Some(bits)
+}
+
+/// The uid of the user called `name` in `/etc/passwd`
+fn uid_of(name: &str) -> Option<u32> {
+    let passwd = fs::read_to_string("/etc/passwd").ok()?;
+    passwd.lines().find_map(|line| {
+        let mut fields = line.split(':');
+        (fields.next()? == name).then_some(())?;
+        fields.nth(1)?.parse().ok()
+    })
+}
+
+/// Which symlinks are followed, as set with `-P`, `-H` and `-L`
+#[derive(Clone, Copy, PartialEq)]
+enum Follow {
+    Never,
+    Paths,
+    Always,
+}
+
+/// A file the expression is evaluated for
+struct Entry<'a> {
+    path: &'a Path,
+    metadata: &'a Metadata,
+}
+
+/// The state of a walk
+struct Walk<'a> {
+    options: Options,
+    follow: Follow,
+    /// when the walk started, in seconds since the epoch
+    now: i64,
+    /// set by `-prune` for a directory that is not to be descended into
+    prune: bool,
+    /// whether something went wrong, but not badly enough to stop
+    failed: bool,
+    out: &'a mut dyn Write,
+    err: &'a mut dyn Write,
+}
+
+impl Walk<'_> {
+    fn report(&mut self, error: &Error) -> Result<()> {
+        writeln!(self.err, "find: {error}")?;
+        self.failed = true;
+        Ok(())
+    }
+
+    fn report_io(&mut self, path: &Path, error: &io::Error) -> Result<()> {
+        self.report(&Error::io(format!("'{}'", path.display()), error))
+    }
+
+    /// Evaluate `expr` for `path` and what is under it. `ancestors` holds the device
+    /// and inode of the directories above, to notice loops when following symlinks.
+    fn visit(
+        &mut self,
+        expr: &mut Expr,
+        path: &Path,
+        depth: usize,
+        ancestors: &mut Vec<(u64, u64)>,
+    ) -> Result<()> {
+        let follow = match self.follow {
+            Follow::Never => false,
+            Follow::Paths => depth == 0,
+            Follow::Always => true,
+        };
+        // a symlink that leads nowhere is taken as itself, even when following
+        let metadata = if follow {
+            fs::metadata(path).or_else(|_| fs::symlink_metadata(path))
+        } else {
+            fs::symlink_metadata(path)
+        };
+        let metadata = match metadata {
+            Ok(metadata) => metadata,
+            Err(e) => return self.report_io(path, &e),
+        };
+        let entry = Entry {
+            path,
+            metadata: &metadata,
+        };
+        let evaluated = depth >= self.options.min_depth;
+
+        self.prune = false;
+        if evaluated && !self.options.depth_first {
+            expr.eval(&entry, self)?;
+        }
+        let descend = metadata.is_dir()
+            && self.options.max_depth.is_none_or(|max| depth < max)
+            && !self.prune;
+        if descend {
+            let id = (metadata.dev(), metadata.ino());
+            if ancestors.contains(&id) {
+                self.report(&Error::new(format!(
+                    "'{}': File system loop detected",
+                    path.display()
+                )))?;
+            } else {
+                ancestors.push(id);
+                self.descend(expr, path, depth, ancestors)?;
+                ancestors.pop();
+            }
+        }
+        if evaluated && self.options.depth_first {
+            expr.eval(&entry, self)?;
+        }
+        Ok(())
+    }
+
+    /// Visit what is in the directory at `path`, in the order of their names
+    fn descend(
+        &mut self,
+        expr: &mut Expr,
+        path: &Path,
+        depth: usize,
+        ancestors: &mut Vec<(u64, u64)>,
+    ) -> Result<()> {
+        let names = fs::read_dir(path).and_then(|entries| {
+            entries
+                .map(|entry| entry.map(|entry| entry.file_name()))
+                .collect::<io::Result<Vec<_>>>()
+        });
+        let mut names = match names {
+            Ok(names) => names,
+            Err(e) => return self.report_io(path, &e),
+        };
+        names.sort();
+        for name in names {
+            self.visit(expr, &path.join(name), depth + 1, ancestors)?;
+        }
+        Ok(())
+    }
+}
+
+impl Expr {
+    /// Whether the expression is true for `entry`, carrying out its actions
+    fn eval(&mut self, entry: &Entry, walk: &mut Walk) -> Result<bool> {
+        let metadata = entry.metadata;
+        Ok(match self {
+            Expr::Not(expr) => !expr.eval(entry, walk)?,
+            Expr::And(left, right) => left.eval(entry, walk)? && right.eval(entry, walk)?,
+            Expr::Or(left, right) => left.eval(entry, walk)? || right.eval(entry, walk)?,
+            Expr::True => true,
+            Expr::False => false,
+            Expr::Name { glob, ignore_case } => {
+                // the name of `/` or `.` is itself
+                let name = entry.path.file_name().unwrap_or(entry.path.as_os_str());
+                if *ignore_case {
+                    fnmatch(glob, &name.as_bytes().to_ascii_lowercase())
+                } else {
+                    fnmatch(glob, name.as_bytes())
+                }
+            }
+            Expr::Path(glob) => fnmatch(glob, entry.path.as_os_str().as_bytes()),
+            Expr::Type(types) => {
+                let file_type = metadata.file_type();
+                types.iter().any(|letter| match letter {
+                    b'f' => file_type.is_file(),
+                    b'd' => file_type.is_dir(),
+                    b'l' => file_type.is_symlink(),
+                    b'p' => file_type.is_fifo(),
+                    b's' => file_type.is_socket(),
+                    b'b' => file_type.is_block_device(),
+                    b'c' => file_type.is_char_device(),
+                    _ => false,
+                })
+            }
+            Expr::Size(comparison, unit) => {
+                comparison.matches(metadata.size().div_ceil(*unit) as i64)
+            }
+            Expr::Age(comparison, unit) => {
+                comparison.matches((walk.now - metadata.mtime()).div_euclid(*unit))
+            }
+            Expr::Newer(seconds, nanoseconds) => {
+                (metadata.mtime(), metadata.mtime_nsec()) > (*seconds, *nanoseconds)
+            }
+            Expr::Perm(how, mode) => {
+                let bits = metadata.mode() & 0o7777;
+                match how {
+                    PermMatch::Exact => bits == *mode,
+                    PermMatch::All => bits & *mode == *mode,
+                    PermMatch::Any => *mode == 0 || bits & *mode != 0,
+                }
+            }
+            Expr::User(uid) => metadata.uid() == *uid,
+            Expr::Print | Expr::Print0 => {
+                walk.out.write_all(entry.path.as_os_str().as_bytes())?;
+                walk.out.write_all(if matches!(self, Expr::Print) {
+                    b"\n"
+                } else {
+                    b"\0"
+                })?;
+                true
+            }
+            Expr::Delete => {
+                // like GNU find, the current directory is left alone
+                if entry.path == Path::new(".") {
+                    return Ok(true);
+                }
+                let removed = if metadata.is_dir() {
+                    fs::remove_dir(entry.path)
+                } else {
+                    fs::remove_file(entry.path)
+                };
+                match removed {
+                    Ok(()) => true,
+                    Err(e) => {
+                        let target = format!("cannot delete '{}'", entry.path.display());
+                        walk.report(&Error::io(target, &e))?;
+                        false
+                    }
+                }
+            }
+            Expr::Exec(exec) => exec.run(entry.path, walk)?,
+            Expr::Prune => {
+                walk.prune = true;
+                true
+            }
+        })
+    }
+
+    /// Run the commands of `-exec ... {} +` for the paths they haven't run for yet
+    fn flush(&mut self, walk: &mut Walk) -> Result<()> {
+        match self {
+            Expr::Not(expr) => expr.flush(walk),
+            Expr::And(left, right) | Expr::Or(left, right) => {
+                left.flush(walk)?;
+                right.flush(walk)
+            }
+            Expr::Exec(exec) => exec.flush(walk),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Exec {
+    /// Run the command for `path`, or gather `path` for the next batch. Whether the
+    /// command succeeded; a batch always counts as true.
+    fn run(&mut self, path: &Path, walk: &mut Walk) -> Result<bool> {
+        if let Some(batch) = &mut self.batch {
+            batch.push(path.as_os_str().to_owned());
+            if batch.iter().map(|path| path.len() + 1).sum::<usize>() >= EXEC_BATCH_BYTES {
+                self.flush(walk)?;
+            }
+            return Ok(true);
+        }
+
+        // `{}` is replaced by the path wherever it appears
+        let path = path.as_os_str().as_bytes();
+        let command: Vec<OsString> = self
+            .command
+            .iter()
+            .map(|arg| OsString::from_vec(replace(arg.as_bytes(), b"{}", path)))
+            .collect();
+        spawn(&command, walk)
+    }
+
+    fn flush(&mut self, walk: &mut Walk) -> Result<()> {
+        let Some(batch) = self.batch.as_mut().filter(|batch| !batch.is_empty()) else {
+            return Ok(());
+        };
+        let command: Vec<OsString> = self
+            .command
+            .iter()
+            .cloned()
+            .chain(batch.drain(..))
+            .collect();
+        if !spawn(&command, walk)? {
+            walk.failed = true;
+        }
+        Ok(())
+    }
+}
+
+/// Run `command`, returning whether it exited with 0
+fn spawn(command: &[OsString], walk: &mut Walk) -> Result<bool> {
+    let Some((program, args)) = command.split_first() else {
+        return Ok(false);
+    };
+    // what was printed so far goes before whatever the command prints
+    walk.out.flush()?;
+    match Command::new(program).args(args).status() {
+        Ok(exit) => Ok(exit.success()),
+        Err(e) => {
+            walk.report(&Error::io(format!("'{}'", program.display()), &e))?;
+            Ok(false)
+        }
+    }
+}
+
+/// `haystack` with every `needle` in it replaced by `replacement`
+fn replace(haystack: &[u8], needle: &[u8], replacement: &[u8]) -> Vec<u8> {
+    let mut replaced = Vec::with_capacity(haystack.len());
+    let mut rest = haystack;
+    while let Some(at) = rest
+        .windows(needle.len())
+        .position(|window| window == needle)
+    {
+        replaced.extend_from_slice(&rest[..at]);
+        replaced.extend_from_slice(replacement);
+        rest = &rest[at + needle.len()..];
+    }
+    replaced.extend_from_slice(rest);
+    replaced
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_cmd::{Command, cargo::cargo_bin, prelude::*};
+    use std::{
+        env,
+        ffi::OsString,
+        fs,
+        os::unix::fs::{PermissionsExt, symlink},
+        path::PathBuf,
+    };
+
+    use super::{Expr, Options, parse_expression, parse_mode};
+
+    /// A tree to search, removed again once dropped
+    struct Tree(PathBuf);
+
+    impl Tree {
+        fn new(name: &str) -> Self {
+            let root = env::temp_dir().join(format!("rizzybox-find-{}-{name}", std::process::id()));
+            fs::create_dir_all(root.join("a/b")).unwrap();
+            fs::create_dir_all(root.join("c")).unwrap();
+            fs::write(root.join("a/x.txt"), "hi\n").unwrap();
+            fs::write(root.join("a/b/big.bin"), [0; 3000]).unwrap();
+            fs::write(root.join("c/y.log"), "").unwrap();
+            fs::set_permissions(root.join("a/x.txt"), fs::Permissions::from_mode(0o755)).unwrap();
+            symlink("../a", root.join("c/link")).unwrap();
+            Self(root)
+        }
+
+        /// Run find from the root of the tree
+        fn find(&self, args: &[&str]) -> (i32, String, String) {
+            let output = Command::cargo_bin(env!("CARGO_PKG_NAME"))
+                .unwrap()
+                .current_dir(&self.0)
+                .arg("find")
+                .args(args)
+                .output()
+                .unwrap();
+            (
+                output.status.code().unwrap(),
+                String::from_utf8(output.stdout).unwrap(),
+                String::from_utf8(output.stderr).unwrap(),
+            )
+        }
+    }
+
+    impl Drop for Tree {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn tokens(tokens: &[&str]) -> Vec<OsString> {
+        tokens.iter().map(OsString::from).collect()
+    }
+
+    #[test]
+    fn parses_expressions() {
+        // Act
+        let (expr, options) = parse_expression(&tokens(&[
+            "-name",
+            "*.rs",
+            "-o",
+            "!",
+            "-type",
+            "d",
+            "-maxdepth",
+            "2",
+        ]))
+        .unwrap();
+
+        // Assert
+        let name = Expr::Name {
+            glob: b"*.rs".to_vec(),
+            ignore_case: false,
+        };
+        let not_dir = Expr::And(
+            Box::new(Expr::Not(Box::new(Expr::Type(b"d".to_vec())))),
+            Box::new(Expr::True),
+        );
+        assert_eq!(
+            expr,
+            Expr::And(
+                Box::new(Expr::Or(Box::new(name), Box::new(not_dir))),
+                Box::new(Expr::Print)
+            )
+        );
+        assert_eq!(
+            options,
+            Options {
+                max_depth: Some(2),
+                ..Options::default()
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_bad_expressions() {
+        for (tokens, message) in [
+            (&["-bogus"][..], "unknown predicate '-bogus'"),
+            (&["-name"], "missing argument to '-name'"),
+            (&["(", "-true"], "missing ')'"),
+            (&["-true", ")"], "unexpected ')'"),
+            (&["-type", "x"], "invalid argument 'x' to '-type'"),
+            (&["-exec", "ls"], "missing argument to '-exec'"),
+        ] {
+            // Act
+            let error = parse_expression(&super::tests::tokens(tokens)).unwrap_err();
+
+            // Assert
+            assert_eq!(error.to_string(), message);
+        }
+    }
+
+    #[test]
+    fn parses_modes() {
+        for (mode, expected) in [
+            ("644", Some(0o644)),
+            ("u+x", Some(0o100)),
+            ("u=rwx,go=rx", Some(0o755)),
+            ("a+r,o-r", Some(0o440)),
+            ("g+s", Some(0o2000)),
+            ("u+q", None),
+            ("9", None),
+        ] {
+            // Act
+            let parsed = parse_mode(mode);
+
+            // Assert
+            assert_eq!(parsed, expected, "{mode}");
+        }
+    }
+
+    #[test]
+    fn evaluates_tests_and_actions() {
+        // Arrange
+        let tree = Tree::new("tests");
+
+        for (args, expected) in [
+            (
+                &[][..],
+                ".\n./a\n./a/b\n./a/b/big.bin\n./a/x.txt\n./c\n./c/link\n./c/y.log\n",
+            ),
+            (
+                &["-name", "*.txt", "-o", "-iname", "*.LOG"],
+                "./a/x.txt\n./c/y.log\n",
+            ),
+            (&["a", "-path", "*/b*"], "a/b\na/b/big.bin\n"),
+            (&["-type", "f", "-size", "+2k"], "./a/b/big.bin\n"),
+            (&["-type", "f", "-perm", "-u+x"], "./a/x.txt\n"),
+            (&["-mmin", "-5", "-type", "l"], "./c/link\n"),
+            (
+                &["-mindepth", "2", "-maxdepth", "2", "-type", "f"],
+                "./a/x.txt\n./c/y.log\n",
+            ),
+            (
+                &["-name", "a", "-prune", "-o", "-type", "f", "-print"],
+                "./c/y.log\n",
+            ),
+            (&["c", "-type", "f", "-print0"], "c/y.log\0"),
+            (&["-L", "c", "-name", "*.txt"], "c/link/x.txt\n"),
+        ] {
+            // Act
+            let (status, out, _) = tree.find(args);
+
+            // Assert
+            assert_eq!((status, out.as_str()), (0, expected), "{args:?}");
+        }
+    }
+
+    #[test]
+    fn runs_commands() {
+        // Arrange
+        let tree = Tree::new("exec");
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.current_dir(&tree.0).args([
+            "find", "a", "-type", "f", "-exec", "echo", "one:{}", ";", "-exec", "echo", "all",
+            "{}", "+",
+        ]);
+
+        // Assert
+        cmd.assert()
+            .success()
+            .stdout("one:a/b/big.bin\none:a/x.txt\nall a/b/big.bin a/x.txt\n");
+    }
+
+    #[test]
+    fn deletes_depth_first() {
+        // Arrange
+        let tree = Tree::new("delete");
+
+        // Act
+        let (status, out, _) = tree.find(&["a", "-delete", "-print"]);
+
+        // Assert
+        assert_eq!(status, 0);
+        assert_eq!(out, "a/b/big.bin\na/b\na/x.txt\na\n");
+        assert!(!tree.0.join("a").exists());
+    }
+
+    #[test]
+    fn reports_errors_and_carries_on() {
+        // Arrange
+        let tree = Tree::new("errors");
+        symlink("..", tree.0.join("a/b/loop")).unwrap();
+
+        // Act
+        let (status, out, err) = tree.find(&["-L", "missing", "a", "-name", "loop"]);
+
+        // Assert
+        assert_eq!(status, 1);
+        assert_eq!(out, "a/b/loop\n");
+        assert_eq!(
+            err,
+            "find: 'missing': No such file or directory\n\
+             find: 'a/b/loop': File system loop detected\n"
+        );
+    }
+
+    #[test]
+    fn stops_at_closed_pipe() {
+        // Arrange
+        let mut cmd = std::process::Command::new(cargo_bin(env!("CARGO_PKG_NAME")));
+
+        // Act
+        cmd.args(["find", env!("CARGO_MANIFEST_DIR")])
+            .stdout(crate::closed_pipe());
+
+        // Assert
+        cmd.assert()
+            .code(crate::error::status::BROKEN_PIPE)
+            .stderr("");
+    }
+}
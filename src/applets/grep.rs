@@ -0,0 +1,674 @@
+use std::{
+    borrow::Cow,
+    collections::VecDeque,
+    ffi::OsString,
+    fs::{self, File},
+    io::{BufRead, BufReader, Read, Write},
+    ops::Range,
+    os::unix::ffi::OsStrExt,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Result;
+use clap::{ArgMatches, Command, CommandFactory, FromArgMatches, Parser, ValueEnum};
+use fancy_regex::{Regex, RegexBuilder};
+
+use super::{Streams, fnmatch};
+use crate::{
+    applet::Applet,
+    error::{self, Error, status},
+    regex::translate,
+};
+
+const MATCH_COLOR: &str = "\x1b[1;31m";
+const FILE_NAME_COLOR: &str = "\x1b[35m";
+const LINE_NUMBER_COLOR: &str = "\x1b[32m";
+const SEPARATOR_COLOR: &str = "\x1b[36m";
+const RESET: &str = "\x1b[0m";
+
+/// What stdin is called in output, like GNU grep calls it
+const STDIN_NAME: &[u8] = b"(standard input)";
+
+/// Print lines that match patterns
+#[derive(Parser)]
+pub struct GrepArgs {
+    /// the PATTERN to search for, unless given with -e; one per line
+    #[arg(required_unless_present = "regexp")]
+    pub pattern: Option<String>,
+
+    /// files to search; stdin if none are given, or the current directory with -r
+    pub file: Vec<OsString>,
+
+    /// search for PATTERN; may be given more than once
+    #[arg(long, short = 'e', value_name = "PATTERN")]
+    pub regexp: Vec<String>,
+
+    /// PATTERN is an extended regular expression
+    #[arg(long, short = 'E', overrides_with = "fixed_strings")]
+    pub extended_regexp: bool,
+
+    /// PATTERN is a plain string
+    #[arg(long, short = 'F', overrides_with = "extended_regexp")]
+    pub fixed_strings: bool,
+
+    /// ignore case distinctions
+    #[arg(long, short)]
+    pub ignore_case: bool,
+
+    /// select lines that do not match
+    #[arg(long, short = 'v')]
+    pub invert_match: bool,
+
+    /// match only whole words
+    #[arg(long, short)]
+    pub word_regexp: bool,
+
+    /// match only whole lines
+    #[arg(long, short = 'x')]
+    pub line_regexp: bool,
+
+    /// print only a count of the selected lines per file
+    #[arg(long, short)]
+    pub count: bool,
+
+    /// print only the names of files with selected lines
+    #[arg(long, short = 'l')]
+    pub files_with_matches: bool,
+
+    /// print only the names of files without selected lines
+    #[arg(long, short = 'L')]
+    pub files_without_match: bool,
+
+    /// print only the matched parts of a line, each on its own line
+    #[arg(long, short)]
+    pub only_matching: bool,
+
+    /// print nothing, and exit with 0 at the first selected line
+    #[arg(long, short, visible_alias = "silent")]
+    pub quiet: bool,
+
+    /// print the line number of each line
+    #[arg(long, short = 'n')]
+    pub line_number: bool,
+
+    /// search directories and everything in them; symlinks found there are skipped
+    #[arg(long, short)]
+    pub recursive: bool,
+
+    /// print NUM lines of context after each selected line
+    #[arg(long, short = 'A', value_name = "NUM")]
+    pub after_context: Option<usize>,
+
+    /// print NUM lines of context before each selected line
+    #[arg(long, short = 'B', value_name = "NUM")]
+    pub before_context: Option<usize>,
+
+    /// print NUM lines of context around each selected line
+    #[arg(long, short = 'C', value_name = "NUM")]
+    pub context: Option<usize>,
+
+    /// search only files whose name matches GLOB
+    #[arg(long, value_name = "GLOB")]
+    pub include: Vec<OsString>,
+
+    /// skip files whose name matches GLOB
+    #[arg(long, value_name = "GLOB")]
+    pub exclude: Vec<OsString>,
+
+    /// when to color matches, file names and line numbers
+    #[arg(
+        long,
+        value_name = "WHEN",
+        default_value = "auto",
+        num_args = 0..=1,
+        default_missing_value = "auto",
+        visible_alias = "colour"
+    )]
+    pub color: ColorWhen,
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ColorWhen {
+    /// when stdout is a terminal
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+pub struct Grep;
+
+impl Applet for Grep {
+    fn name(&self) -> &'static str {
+        "grep"
+    }
+
+    fn command(&self) -> Command {
+        GrepArgs::command()
+    }
+
+    fn usage_status(&self) -> i32 {
+        status::TROUBLE
+    }
+
+    fn run(&self, matches: &ArgMatches, streams: &mut Streams) -> Result<i32> {
+        let args = GrepArgs::from_arg_matches(matches)?;
+        let color = match args.color {
+            ColorWhen::Auto => streams.stdout_is_terminal,
+            ColorWhen::Always => true,
+            ColorWhen::Never => false,
+        };
+        grep_command(
+            &args,
+            color,
+            &mut streams.stdin,
+            &mut streams.stdout,
+            &mut streams.stderr,
+        )
+    }
+}
+
+/// Search the files named in `args`, or `input` if there are none, printing what is
+/// selected to `out`. Files that can't be read are reported to `err` and skipped.
+/// Returns 0 if a line was selected, 1 if none was, and 2 if something went wrong,
+/// unless `--quiet` found a line regardless.
+pub fn grep_command(
+    args: &GrepArgs,
+    color: bool,
+    input: &mut dyn Read,
+    out: &mut dyn Write,
+    err: &mut dyn Write,
+) -> Result<i32> {
+    // with -e, what clap took for the pattern is the first file
+    let (patterns, files) = match &args.pattern {
+        Some(pattern) if args.regexp.is_empty() => (vec![pattern.clone()], args.file.clone()),
+        first_file => (
+            args.regexp.clone(),
+            first_file
+                .iter()
+                .map(OsString::from)
+                .chain(args.file.iter().cloned())
+                .collect(),
+        ),
+    };
+
+    let mut grep = Searcher {
+        args,
+        regex: build_regex(&patterns, args)?,
+        color,
+        with_file_name: files.len() > 1,
+        selected: false,
+        trouble: false,
+        out,
+        err,
+    };
+
+    if files.is_empty() && args.recursive {
+        grep.with_file_name = true;
+        grep.search_dir(Path::new(""))?;
+    } else if files.is_empty() {
+        grep.search(STDIN_NAME, input)?;
+    }
+    for file in &files {
+        if grep.done() {
+            break;
+        }
+        let path = Path::new(file);
+        if file == "-" {
+            grep.search(STDIN_NAME, input)?;
+        } else if args.recursive && path.is_dir() {
+            grep.with_file_name = true;
+            grep.search_dir(path)?;
+        } else if grep.included(path) {
+            grep.search_file(path)?;
+        }
+    }
+
+    Ok(if grep.selected && (args.quiet || !grep.trouble) {
+        0
+    } else if grep.trouble {
+        status::TROUBLE
+    } else {
+        status::FAILURE
+    })
+}
+
+/// Combine `patterns` into one regex, the way the options in `args` ask for
+pub fn build_regex(patterns: &[String], args: &GrepArgs) -> Result<Regex> {
+    let alternatives: Vec<String> = patterns
+        .iter()
+        .flat_map(|pattern| pattern.split('\n'))
+        .map(|pattern| {
+            if args.fixed_strings {
+                format!("(?:{})", fancy_regex::escape(pattern))
+            } else {
+                format!("(?:{})", translate(pattern, args.extended_regexp))
+            }
+        })
+        .collect();
+    let mut pattern = alternatives.join("|");
+    if args.line_regexp {
+        pattern = format!("^(?:{pattern})$");
+    } else if args.word_regexp {
+        pattern = format!(r"(?<!\w)(?:{pattern})(?!\w)");
+    }
+    RegexBuilder::new(&pattern)
+        .case_insensitive(args.ignore_case)
+        .build()
+        .map_err(|e| Error::new(e).with_status(status::TROUBLE).into())
+}
+
+/// The state of a search across files
+struct Searcher<'a> {
+    args: &'a GrepArgs,
+    regex: Regex,
+    color: bool,
+    with_file_name: bool,
+    /// whether any line was selected, or with -L, any file listed
+    selected: bool,
+    /// whether a file could not be searched
+    trouble: bool,
+    out: &'a mut dyn Write,
+    err: &'a mut dyn Write,
+}
+
+impl Searcher<'_> {
+    /// Whether there's no point in searching any further
+    fn done(&self) -> bool {
+        self.args.quiet && self.selected
+    }
+
+    /// Whether `path` passes `--include` and `--exclude`
+    fn included(&self, path: &Path) -> bool {
+        let name = path.file_name().unwrap_or(path.as_os_str()).as_bytes();
+        let matches = |globs: &[OsString]| globs.iter().any(|glob| fnmatch(glob.as_bytes(), name));
+        (self.args.include.is_empty() || matches(&self.args.include))
+            && !matches(&self.args.exclude)
+    }
+
+    fn report(&mut self, error: &Error) -> Result<()> {
+        writeln!(self.err, "grep: {error}")?;
+        self.trouble = true;
+        Ok(())
+    }
+
+    fn search_file(&mut self, path: &Path) -> Result<()> {
+        let result = File::open(path)
+            .map_err(Into::into)
+            .and_then(|mut file| self.search(path.as_os_str().as_bytes(), &mut file));
+        match result {
+            Err(e) => match e.downcast::<std::io::Error>() {
+                // there's no point in going on once the output is gone
+                Ok(e) if error::is_write_error(&e) => Err(e.into()),
+                Ok(e) => self.report(&Error::io(path.display(), &e)),
+                Err(e) => Err(e),
+            },
+            Ok(()) => Ok(()),
+        }
+    }
+
+    /// Search the files under `dir`, in the order of their names. Symlinks and
+    /// special files are skipped. The empty path stands for the current directory,
+    /// with names printed relative to it.
+    fn search_dir(&mut self, dir: &Path) -> Result<()> {
+        let read_from = if dir.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            dir
+        };
+        let mut entries = match fs::read_dir(read_from)
+            .and_then(|entries| entries.collect::<Result<Vec<_>, _>>())
+        {
+            Ok(entries) => entries,
+            Err(e) => return self.report(&Error::io(read_from.display(), &e)),
+        };
+        entries.sort_by_key(|entry| entry.file_name());
+
+        for entry in entries {
+            if self.done() {
+                break;
+            }
+            let path: PathBuf = dir.join(entry.file_name());
+            match entry.file_type() {
+                Ok(file_type) if file_type.is_dir() => self.search_dir(&path)?,
+                Ok(file_type) if file_type.is_file() && self.included(&path) => {
+                    self.search_file(&path)?;
+                }
+                Ok(_) => {}
+                Err(e) => self.report(&Error::io(path.display(), &e))?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Search `input`, called `name` in the output
+    fn search(&mut self, name: &[u8], input: &mut dyn Read) -> Result<()> {
+        let args = self.args;
+        let mut input = BufReader::new(input);
+        // like GNU grep, files with a NUL early on are binary, and only said to match
+        let binary = input.fill_buf()?.contains(&0);
+        let lists_files = args.files_with_matches || args.files_without_match;
+        let stops_at_first = args.quiet || lists_files;
+        let prints_lines = !(stops_at_first || args.count);
+
+        let before = args.before_context.or(args.context).unwrap_or(0);
+        let after = args.after_context.or(args.context).unwrap_or(0);
+        let context = (before > 0 || after > 0) && !args.only_matching;
+        let mut previous: VecDeque<(u64, Vec<u8>)> = VecDeque::with_capacity(before);
+        let mut after_left = 0;
+        let mut last_printed = None;
+
+        let mut count = 0u64;
+        let mut number = 0u64;
+        let mut line = Vec::new();
+        loop {
+            line.clear();
+            if input.read_until(b'\n', &mut line)? == 0 {
+                break;
+            }
+            number += 1;
+            if line.last() == Some(&b'\n') {
+                line.pop();
+            }
+            let text = String::from_utf8_lossy(&line);
+            let is_match = self
+                .regex
+                .is_match(&text)
+                .map_err(|e| Error::new(e).with_status(status::TROUBLE))?;
+
+            if is_match == args.invert_match {
+                if !prints_lines || !context {
+                    continue;
+                }
+                if after_left > 0 {
+                    after_left -= 1;
+                    self.print_line(name, number, b'-', &line, None)?;
+                    last_printed = Some(number);
+                } else if before > 0 {
+                    if previous.len() == before {
+                        previous.pop_front();
+                    }
+                    previous.push_back((number, line.clone()));
+                }
+                continue;
+            }
+
+            count += 1;
+            if stops_at_first {
+                break;
+            }
+            if !prints_lines {
+                continue;
+            }
+            if binary {
+                self.out.write_all(b"Binary file ")?;
+                self.out.write_all(name)?;
+                self.out.write_all(b" matches\n")?;
+                break;
+            }
+
+            let first = previous.front().map_or(number, |(number, _)| *number);
+            if context && last_printed.is_some_and(|last| last + 1 < first) {
+                self.write_colored(b"--", SEPARATOR_COLOR)?;
+                self.out.write_all(b"\n")?;
+            }
+            for (number, line) in previous.drain(..) {
+                self.print_line(name, number, b'-', &line, None)?;
+            }
+            if args.only_matching {
+                if !args.invert_match {
+                    self.print_matches(name, number, &text)?;
+                }
+            } else {
+                // lines that aren't valid UTF-8 are printed as they are, uncolored
+                let colored =
+                    (self.color && !args.invert_match && matches!(text, Cow::Borrowed(_)))
+                        .then_some(&*text);
+                self.print_line(name, number, b':', &line, colored)?;
+            }
+            last_printed = Some(number);
+            after_left = after;
+        }
+
+        if count > 0 && !args.files_without_match {
+            self.selected = true;
+        }
+        if args.quiet {
+            return Ok(());
+        }
+        if lists_files {
+            if (count > 0) == args.files_with_matches {
+                self.selected = true;
+                self.write_colored(name, FILE_NAME_COLOR)?;
+                self.out.write_all(b"\n")?;
+            }
+        } else if args.count {
+            if self.with_file_name {
+                self.write_colored(name, FILE_NAME_COLOR)?;
+                self.write_colored(b":", SEPARATOR_COLOR)?;
+            }
+            writeln!(self.out, "{count}")?;
+        }
+        Ok(())
+    }
+
+    /// Print a selected line (`separator` is `:`) or one of context (`-`), behind the
+    /// file name and line number if asked for. `colored` is the line as text, to
+    /// print with its matches colored.
+    fn print_line(
+        &mut self,
+        name: &[u8],
+        number: u64,
+        separator: u8,
+        line: &[u8],
+        colored: Option<&str>,
+    ) -> Result<()> {
+        self.print_prefix(name, number, separator)?;
+        match colored {
+            Some(text) => {
+                let mut end = 0;
+                for found in self.matches(text)? {
+                    self.out.write_all(&text.as_bytes()[end..found.start])?;
+                    end = found.end;
+                    self.write_colored(text[found].as_bytes(), MATCH_COLOR)?;
+                }
+                self.out.write_all(&text.as_bytes()[end..])?;
+            }
+            None => self.out.write_all(line)?,
+        }
+        self.out.write_all(b"\n")?;
+        Ok(())
+    }
+
+    /// Print each non-empty match in `text` on its own line, for `--only-matching`
+    fn print_matches(&mut self, name: &[u8], number: u64, text: &str) -> Result<()> {
+        for found in self.matches(text)? {
+            self.print_prefix(name, number, b':')?;
+            self.write_colored(text[found].as_bytes(), MATCH_COLOR)?;
+            self.out.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+
+    /// Where the non-empty matches in `text` are
+    fn matches(&self, text: &str) -> Result<Vec<Range<usize>>> {
+        let mut ranges = Vec::new();
+        for found in self.regex.find_iter(text) {
+            let found = found.map_err(|e| Error::new(e).with_status(status::TROUBLE))?;
+            if !found.as_str().is_empty() {
+                ranges.push(found.range());
+            }
+        }
+        Ok(ranges)
+    }
+
+    fn print_prefix(&mut self, name: &[u8], number: u64, separator: u8) -> Result<()> {
+        if self.with_file_name {
+            self.write_colored(name, FILE_NAME_COLOR)?;
+            self.write_colored(&[separator], SEPARATOR_COLOR)?;
+        }
+        if self.args.line_number {
+            self.write_colored(number.to_string().as_bytes(), LINE_NUMBER_COLOR)?;
+            self.write_colored(&[separator], SEPARATOR_COLOR)?;
+        }
+        Ok(())
+    }
+
+    fn write_colored(&mut self, bytes: &[u8], color: &str) -> Result<()> {
+        if self.color {
+            write!(self.out, "{color}")?;
+            self.out.write_all(bytes)?;
+            write!(self.out, "{RESET}")?;
+        } else {
+            self.out.write_all(bytes)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_cmd::{Command, cargo::cargo_bin, prelude::*};
+    use clap::Parser;
+    use std::{env, fs};
+
+    use super::{GrepArgs, grep_command};
+    use crate::applets::fnmatch;
+
+    const LINES: &str = "one\ntwo foo\nthree\nfour\nfive foo bar\nsix\nseven\neight\nnine foo\n";
+
+    /// Run grep in-process over `input` with the arguments in `args`
+    fn grep(args: &[&str], color: bool, input: &str) -> (i32, String) {
+        let args =
+            GrepArgs::try_parse_from(std::iter::once("grep").chain(args.iter().copied())).unwrap();
+        let (mut out, mut err) = (Vec::new(), Vec::new());
+        let status = grep_command(&args, color, &mut input.as_bytes(), &mut out, &mut err).unwrap();
+        (status, String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn prints_context_around_matches() {
+        // Act
+        let (status, out) = grep(&["-n", "-C1", "foo"], false, LINES);
+
+        // Assert
+        assert_eq!(status, 0);
+        assert_eq!(
+            out,
+            "1-one\n2:two foo\n3-three\n4-four\n5:five foo bar\n6-six\n--\n8-eight\n9:nine foo\n"
+        );
+    }
+
+    #[test]
+    fn selects_lines() {
+        for (args, expected) in [
+            (&["-v", "-c", "o"][..], "4\n"),
+            (&["-o", "-E", "f[a-z]+"], "foo\nfour\nfive\nfoo\nfoo\n"),
+            (&["-w", "-i", "FO"], ""),
+            (&["-x", "-F", "six"], "six\n"),
+            (&["-e", "six", "-e", "^o"], "one\nsix\n"),
+            (&["-q", "foo"], ""),
+        ] {
+            // Act
+            let (_, out) = grep(args, false, LINES);
+
+            // Assert
+            assert_eq!(out, expected, "{args:?}");
+        }
+    }
+
+    #[test]
+    fn colors_matches() {
+        // Act
+        let (_, out) = grep(&["bar"], true, LINES);
+
+        // Assert
+        assert_eq!(out, "five foo \x1b[1;31mbar\x1b[0m\n");
+    }
+
+    #[test]
+    fn matches_file_names() {
+        for (glob, name, expected) in [
+            ("*.log", "b.log", true),
+            ("*.log", "b.log.gz", false),
+            ("[!.]*", ".hidden", false),
+            ("a?[0-9]", "ab7", true),
+            (r"\*", "*", true),
+            ("[ab", "[ab", true),
+        ] {
+            // Act
+            let matched = fnmatch(glob.as_bytes(), name.as_bytes());
+
+            // Assert
+            assert_eq!(matched, expected, "{glob} {name}");
+        }
+    }
+
+    #[test]
+    fn searches_directories_recursively() {
+        // Arrange
+        let dir = env::temp_dir().join(format!("rizzybox-grep-{}", std::process::id()));
+        fs::create_dir_all(dir.join("sub")).unwrap();
+        fs::write(dir.join("a.txt"), LINES).unwrap();
+        fs::write(dir.join("sub/b.log"), "foo\n").unwrap();
+        fs::write(dir.join("sub/c.log"), "bar\n").unwrap();
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.current_dir(&dir).args([
+            "grep",
+            "-r",
+            "-l",
+            "--include=*.log",
+            "--exclude=c*",
+            "foo\nbar",
+        ]);
+
+        // Assert
+        cmd.assert().success().stdout("sub/b.log\n");
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn exits_with_1_without_a_match_and_2_on_trouble() {
+        // Arrange
+        let mut no_match = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+        let mut missing = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+        let mut bad_regex = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        no_match.args(["grep", "nothing"]).write_stdin(LINES);
+        missing
+            .args(["grep", "foo", "/nonexistent", "-"])
+            .write_stdin(LINES);
+        bad_regex.args(["grep", "-E", "(foo"]).write_stdin(LINES);
+
+        // Assert
+        no_match.assert().code(1).stdout("");
+        missing
+            .assert()
+            .code(2)
+            .stdout(predicates::str::contains("(standard input):two foo"))
+            .stderr("grep: /nonexistent: No such file or directory\n");
+        bad_regex.assert().code(2);
+    }
+
+    #[test]
+    fn stops_at_closed_pipe() {
+        // Arrange
+        let mut cmd = std::process::Command::new(cargo_bin(env!("CARGO_PKG_NAME")));
+
+        // Act
+        cmd.args([
+            "grep",
+            "rizzybox",
+            concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml"),
+        ])
+        .stdout(crate::closed_pipe());
+
+        // Assert
+        cmd.assert()
+            .code(crate::error::status::BROKEN_PIPE)
+            .stderr("");
+    }
+}
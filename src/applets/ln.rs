@@ -0,0 +1,106 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::{ArgMatches, Command, CommandFactory, FromArgMatches, Parser};
+
+use super::Streams;
+use crate::{applet::Applet, error::Error};
+
+/// Create links to files, hard links unless --symlink is given
+#[derive(Parser)]
+pub struct LnArgs {
+    /// remove existing destination files
+    #[arg(long, short)]
+    pub force: bool,
+
+    /// make symbolic links instead of hard links
+    #[arg(long, short)]
+    pub symlink: bool,
+
+    pub source: PathBuf,
+
+    pub destination: PathBuf,
+}
+
+pub struct Ln;
+
+impl Applet for Ln {
+    fn name(&self) -> &'static str {
+        "ln"
+    }
+
+    fn command(&self) -> Command {
+        LnArgs::command()
+    }
+
+    fn run(&self, matches: &ArgMatches, _streams: &mut Streams) -> Result<i32> {
+        let args = LnArgs::from_arg_matches(matches)?;
+        ln_command(&args)?;
+        Ok(0)
+    }
+}
+
+pub fn ln_command(args: &LnArgs) -> Result<()> {
+    let (source, destination) = (&args.source, &args.destination);
+    if args.force {
+        // if a file doesn't exist, remove_file will fail, so ensure it exists first
+        if std::fs::metadata(destination).is_ok() {
+            let _ = std::fs::remove_file(destination);
+        }
+    }
+
+    let (kind, result) = if args.symlink {
+        (
+            "symbolic link",
+            std::os::unix::fs::symlink(source, destination),
+        )
+    } else {
+        ("hard link", std::fs::hard_link(source, destination))
+    };
+    result.map_err(|e| {
+        Error::io(
+            format!("failed to create {kind} '{}'", destination.display()),
+            &e,
+        )
+    })?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_cmd::Command;
+    use std::{fs, os::unix::fs::MetadataExt};
+
+    #[test]
+    fn creates_hard_links() {
+        // Arrange
+        let dir = std::env::temp_dir().join(format!("rizzybox-ln-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let (source, destination) = (dir.join("source"), dir.join("destination"));
+        fs::write(&source, "linked").unwrap();
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.arg("ln").arg(&source).arg(&destination);
+
+        // Assert
+        cmd.assert().success();
+        let ino = |path| fs::metadata(path).unwrap().ino();
+        assert_eq!(ino(&source), ino(&destination));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn reports_existing_destination() {
+        // Arrange
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.args(["ln", "-s", "/etc/hosts", "/"]);
+
+        // Assert
+        cmd.assert().code(1);
+        cmd.assert()
+            .stderr("ln: failed to create symbolic link '/': File exists\n");
+    }
+}
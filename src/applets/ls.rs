@@ -0,0 +1,248 @@
+use std::{
+    collections::BTreeSet,
+    ffi::{OsStr, OsString},
+    fs,
+    io::{self, Write},
+    os::unix::{
+        ffi::OsStrExt,
+        fs::{FileTypeExt, MetadataExt},
+    },
+    path::{Path, PathBuf},
+};
+
+use anyhow::Result;
+use clap::{ArgMatches, Command, CommandFactory, FromArgMatches, Parser};
+use serde::Serialize;
+
+use super::{Streams, write_json};
+use crate::{
+    applet::Applet,
+    error::{Error, status},
+};
+
+/// List information about the FILEs (the current directory by default)
+#[derive(Parser)]
+pub struct LsArgs {
+    /// do not ignore entries starting with '.'
+    #[arg(long, short)]
+    pub all: bool,
+
+    /// the PATH to list
+    #[arg(default_value = ".", hide_default_value = true)]
+    pub path: PathBuf,
+
+    /// print the entries and their metadata as JSON, for scripts
+    #[arg(long)]
+    pub json: bool,
+}
+
+pub struct Ls;
+
+impl Applet for Ls {
+    fn name(&self) -> &'static str {
+        "ls"
+    }
+
+    fn command(&self) -> Command {
+        LsArgs::command()
+    }
+
+    fn usage_status(&self) -> i32 {
+        status::TROUBLE
+    }
+
+    fn run(&self, matches: &ArgMatches, streams: &mut Streams) -> Result<i32> {
+        let args = LsArgs::from_arg_matches(matches)?;
+        ls_command(&args, &mut streams.stdout)?;
+        Ok(0)
+    }
+}
+
+pub fn ls_command(args: &LsArgs, out: &mut impl Write) -> Result<()> {
+    let path = &args.path;
+    if path.is_file() {
+        if args.json {
+            return write_json(&[LsEntry::new(path.as_os_str(), path)?], out);
+        }
+        out.write_all(path.as_os_str().as_bytes())?;
+        out.write_all(b"\n")?;
+        return Ok(());
+    }
+
+    let file_listings = list(path, args.all)?;
+
+    if args.json {
+        let entries = file_listings
+            .iter()
+            .map(|name| LsEntry::new(name, &path.join(name)))
+            .collect::<Result<Vec<_>>>()?;
+        return write_json(&entries, out);
+    }
+
+    for entry in &file_listings {
+        out.write_all(entry.as_bytes())?;
+        out.write_all(b"\n")?;
+    }
+    Ok(())
+}
+
+/// The names of the entries in the directory at `path`, sorted bytewise. Unless
+/// `all` is set, the ones starting with `.` are left out.
+pub fn list(path: &Path, all: bool) -> Result<BTreeSet<OsString>> {
+    let entries = fs::read_dir(path)
+        .and_then(|dir| {
+            dir.map(|res| res.map(|e| e.file_name()))
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .map_err(|e| {
+            let target = if e.kind() == io::ErrorKind::NotFound {
+                format!("cannot access '{}'", path.display())
+            } else {
+                format!("cannot open directory '{}'", path.display())
+            };
+            Error::io(target, &e).with_status(status::TROUBLE)
+        })?;
+
+    let mut file_listings = BTreeSet::new();
+    if all {
+        file_listings.insert(OsString::from("."));
+        file_listings.insert(OsString::from(".."));
+    }
+    file_listings.extend(
+        entries
+            .into_iter()
+            .filter(|file_name| all || !file_name.as_bytes().starts_with(b".")),
+    );
+    Ok(file_listings)
+}
+
+/// An entry of `ls --json`. Symlinks are described themselves rather than what they
+/// point to, which is given as their `target`. JSON has no room for names that
+/// aren't valid UTF-8, so those are given with U+FFFD in place of the invalid bytes.
+#[derive(Debug, Serialize)]
+pub struct LsEntry {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    pub size: u64,
+    /// permission bits in octal, e.g. `0755`
+    pub mode: String,
+    pub uid: u32,
+    pub gid: u32,
+    pub nlink: u64,
+    /// last modification, in seconds since the epoch
+    pub modified: i64,
+    pub target: Option<String>,
+}
+
+impl LsEntry {
+    /// Describe the entry at `path`, listed as `name`
+    pub fn new(name: &OsStr, path: &Path) -> Result<Self> {
+        let metadata = fs::symlink_metadata(path).map_err(|e| {
+            Error::io(format!("cannot access '{}'", path.display()), &e)
+                .with_status(status::TROUBLE)
+        })?;
+        let file_type = metadata.file_type();
+        let kind = if file_type.is_dir() {
+            "directory"
+        } else if file_type.is_file() {
+            "file"
+        } else if file_type.is_symlink() {
+            "symlink"
+        } else if file_type.is_fifo() {
+            "fifo"
+        } else if file_type.is_socket() {
+            "socket"
+        } else if file_type.is_block_device() {
+            "block_device"
+        } else if file_type.is_char_device() {
+            "char_device"
+        } else {
+            "unknown"
+        };
+        let target = if file_type.is_symlink() {
+            fs::read_link(path)
+                .ok()
+                .map(|target| target.to_string_lossy().into_owned())
+        } else {
+            None
+        };
+
+        Ok(Self {
+            name: name.to_string_lossy().into_owned(),
+            kind,
+            size: metadata.len(),
+            mode: format!("{:04o}", metadata.mode() & 0o7777),
+            uid: metadata.uid(),
+            gid: metadata.gid(),
+            nlink: metadata.nlink(),
+            modified: metadata.mtime(),
+            target,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_cmd::{Command, cargo::cargo_bin, prelude::*};
+    use std::{
+        ffi::OsStr,
+        fs,
+        os::unix::{ffi::OsStrExt, fs::symlink},
+    };
+
+    #[test]
+    fn json_describes_entries() {
+        // Arrange
+        let dir = std::env::temp_dir().join(format!("rizzybox-ls-json-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("file"), "four").unwrap();
+        symlink("file", dir.join("link")).unwrap();
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.args(["ls", "--json"]).arg(&dir);
+
+        // Assert
+        let output = cmd.assert().success().get_output().stdout.clone();
+        let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(json[0]["name"], "file");
+        assert_eq!(json[0]["type"], "file");
+        assert_eq!(json[0]["size"], 4);
+        assert_eq!(json[1]["name"], "link");
+        assert_eq!(json[1]["type"], "symlink");
+        assert_eq!(json[1]["target"], "file");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn lists_invalid_utf8_names() {
+        // Arrange
+        let dir = std::env::temp_dir().join(format!("rizzybox-ls-bytes-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(OsStr::from_bytes(b"caf\xe9")), "").unwrap();
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.arg("ls").arg(&dir);
+
+        // Assert
+        cmd.assert().success().stdout(&b"caf\xe9\n"[..]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn stops_at_closed_pipe() {
+        // Arrange
+        let mut cmd = std::process::Command::new(cargo_bin(env!("CARGO_PKG_NAME")));
+
+        // Act
+        cmd.args(["ls", env!("CARGO_MANIFEST_DIR")])
+            .stdout(crate::closed_pipe());
+
+        // Assert
+        cmd.assert()
+            .code(crate::error::status::BROKEN_PIPE)
+            .stderr("");
+    }
+}
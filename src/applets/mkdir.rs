@@ -0,0 +1,61 @@
+use std::{
+    io::{self, Write},
+    path::PathBuf,
+};
+
+use anyhow::Result;
+use clap::{ArgMatches, Command, CommandFactory, FromArgMatches, Parser};
+
+use super::Streams;
+use crate::{
+    applet::Applet,
+    error::{self, Error},
+};
+
+/// Create directories if they do not already exist
+#[derive(Parser)]
+pub struct MkdirArgs {
+    /// directories to create
+    #[arg(required = true)]
+    pub dirs: Vec<PathBuf>,
+
+    /// create parent directories as needed
+    #[arg(long, short, env = "RZ_MKDIR_PARENTS")]
+    pub parents: bool,
+}
+
+pub struct Mkdir;
+
+impl Applet for Mkdir {
+    fn name(&self) -> &'static str {
+        "mkdir"
+    }
+
+    fn command(&self) -> Command {
+        MkdirArgs::command()
+    }
+
+    fn run(&self, matches: &ArgMatches, streams: &mut Streams) -> Result<i32> {
+        let args = MkdirArgs::from_arg_matches(matches)?;
+        Ok(mkdir_command(&args, &mut streams.stderr)?)
+    }
+}
+
+/// Create DIRS, reporting the ones that can't be to `err` and returning 1 if there
+/// were any
+pub fn mkdir_command(args: &MkdirArgs, err: &mut impl Write) -> io::Result<i32> {
+    let mut status = 0;
+    for dir in &args.dirs {
+        let result = if args.parents {
+            std::fs::create_dir_all(dir)
+        } else {
+            std::fs::create_dir(dir)
+        };
+        if let Err(e) = result {
+            let target = format!("cannot create directory '{}'", dir.display());
+            writeln!(err, "mkdir: {}", Error::io(target, &e))?;
+            status = error::status::FAILURE;
+        }
+    }
+    Ok(status)
+}
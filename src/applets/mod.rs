@@ -0,0 +1,295 @@
+#[cfg(feature = "basename")]
+pub mod basename;
+#[cfg(feature = "cat")]
+pub mod cat;
+#[cfg(feature = "clear")]
+pub mod clear;
+#[cfg(feature = "diag")]
+pub mod diag;
+#[cfg(feature = "dirname")]
+pub mod dirname;
+#[cfg(feature = "echo")]
+pub mod echo;
+#[cfg(feature = "env")]
+pub mod env;
+#[cfg(feature = "expand")]
+pub mod expand;
+#[cfg(feature = "false")]
+pub mod r#false;
+#[cfg(feature = "find")]
+pub mod find;
+#[cfg(feature = "grep")]
+pub mod grep;
+pub mod ln;
+#[cfg(feature = "ls")]
+pub mod ls;
+pub mod mkdir;
+#[cfg(feature = "nproc")]
+pub mod nproc;
+#[cfg(feature = "nsenter")]
+pub mod nsenter;
+#[cfg(feature = "pathmunge")]
+pub mod pathmunge;
+#[cfg(feature = "sed")]
+pub mod sed;
+pub mod sh;
+#[cfg(feature = "sleep")]
+pub mod sleep;
+#[cfg(feature = "stem")]
+pub mod stem;
+#[cfg(feature = "true")]
+pub mod r#true;
+#[cfg(feature = "uname")]
+pub mod uname;
+pub mod which;
+#[cfg(feature = "yes")]
+pub mod yes;
+
+#[cfg(feature = "highlight")]
+use std::fmt;
+use std::io::{self, IsTerminal, Read, Write};
+
+#[cfg(feature = "highlight")]
+use bat::PrettyPrinter;
+#[cfg(any(feature = "cat", feature = "echo"))]
+use clap::Args;
+#[cfg(feature = "highlight")]
+use clap::{Command, builder::PossibleValuesParser};
+use serde::Serialize;
+
+use crate::error::{self, Error};
+
+/// The standard streams an applet reads from and writes to.
+///
+/// Applets never touch the process-wide handles directly, which lets the shell
+/// run a pipeline of applets as threads connected by pipes within one process.
+#[cfg_attr(not(feature = "full"), allow(dead_code))] // what gets read depends on the applets built in
+pub struct Streams {
+    pub stdin: Box<dyn Read + Send>,
+    pub stdout: Box<dyn Write + Send>,
+    pub stderr: Box<dyn Write + Send>,
+    /// whether `stdin` is attached to a terminal
+    pub stdin_is_terminal: bool,
+    /// whether `stdout` is attached to a terminal; decides on colored output
+    pub stdout_is_terminal: bool,
+}
+
+impl Streams {
+    /// The streams of the running process
+    pub fn inherit() -> Self {
+        Self {
+            stdin: Box::new(io::stdin()),
+            stdout: Box::new(Output(io::stdout())),
+            stderr: Box::new(io::stderr()),
+            stdin_is_terminal: io::stdin().is_terminal(),
+            stdout_is_terminal: io::stdout().is_terminal(),
+        }
+    }
+
+    /// Report a failure that the applet carries on after, as `applet: target: reason`
+    pub fn report(&mut self, applet: &str, error: &Error) -> io::Result<()> {
+        writeln!(self.stderr, "{applet}: {error}")
+    }
+}
+
+/// Wraps the output of an applet, marking the errors from writing to it as
+/// [`error::write_error`]s so they can be told apart from those of its input
+pub struct Output<W>(pub W);
+
+impl<W: Write> Write for Output<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.write(buf).map_err(error::write_error)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush().map_err(error::write_error)
+    }
+}
+
+/// Print `value` as pretty-printed JSON, for the applets that take `--json`
+pub fn write_json(value: &impl Serialize, out: &mut dyn Write) -> anyhow::Result<()> {
+    let mut json = serde_json::to_vec_pretty(value)?;
+    json.push(b'\n');
+    out.write_all(&json)?;
+    Ok(())
+}
+
+/// Whether `name` matches the shell wildcard `pattern`, in which `*` stands for any
+/// run of bytes, `?` for any one, and `[...]` for one of a set, like `[a-z]` or
+/// `[!.]`. A `\` takes the byte after it literally.
+#[cfg(any(feature = "find", feature = "grep"))]
+pub fn fnmatch(pattern: &[u8], name: &[u8]) -> bool {
+    let (mut p, mut n) = (0, 0);
+    // where to resume after the last `*` if what follows it stops matching
+    let mut backtrack = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some(b'*') => {
+                p += 1;
+                backtrack = Some((p, n));
+                continue;
+            }
+            Some(b'?') => {
+                p += 1;
+                n += 1;
+                continue;
+            }
+            Some(b'[') => match match_bracket(&pattern[p..], name[n]) {
+                Some((true, len)) => {
+                    p += len;
+                    n += 1;
+                    continue;
+                }
+                // a `[` that is never closed is an ordinary byte
+                None if name[n] == b'[' => {
+                    p += 1;
+                    n += 1;
+                    continue;
+                }
+                _ => {}
+            },
+            Some(b'\\') if pattern.get(p + 1) == Some(&name[n]) => {
+                p += 2;
+                n += 1;
+                continue;
+            }
+            Some(&byte) if byte != b'\\' && byte == name[n] => {
+                p += 1;
+                n += 1;
+                continue;
+            }
+            _ => {}
+        }
+        match backtrack {
+            Some((star, from)) => {
+                p = star;
+                n = from + 1;
+                backtrack = Some((star, from + 1));
+            }
+            None => return false,
+        }
+    }
+    pattern[p..].iter().all(|&byte| byte == b'*')
+}
+
+/// Whether `byte` is in the set that `pattern` starts with, and the length of the set;
+/// `None` if the `[` is never closed
+#[cfg(any(feature = "find", feature = "grep"))]
+fn match_bracket(pattern: &[u8], byte: u8) -> Option<(bool, usize)> {
+    let mut i = 1;
+    let negated = matches!(pattern.get(i), Some(b'!' | b'^'));
+    if negated {
+        i += 1;
+    }
+    let mut matched = false;
+    let mut first = true;
+    loop {
+        let &low = pattern.get(i)?;
+        if low == b']' && !first {
+            return Some((matched != negated, i + 1));
+        }
+        first = false;
+        if let (Some(b'-'), Some(&high)) = (pattern.get(i + 1), pattern.get(i + 2))
+            && high != b']'
+        {
+            matched |= (low..=high).contains(&byte);
+            i += 3;
+        } else {
+            matched |= low == byte;
+            i += 1;
+        }
+    }
+}
+
+/// Syntax highlighting options of the applets that print through bat. Without the
+/// `highlight` feature there are none, and output is always plain.
+#[cfg(any(feature = "cat", feature = "echo"))]
+#[derive(Args)]
+pub struct HighlightArgs {
+    /// language to use for syntax highlighting
+    #[cfg(feature = "highlight")]
+    #[arg(long, short, default_value = "txt")]
+    pub language: String,
+
+    /// theme to use for colored output
+    #[cfg(feature = "highlight")]
+    #[arg(long, short, default_value = "Dracula")]
+    pub theme: String,
+}
+
+#[cfg(feature = "echo")]
+impl HighlightArgs {
+    /// Write `bytes` to stdout, highlighted if it is a terminal
+    pub fn write(&self, bytes: &[u8], streams: &mut Streams) -> anyhow::Result<()> {
+        #[cfg(feature = "highlight")]
+        if streams.stdout_is_terminal {
+            let mut pretty_printer = PrettyPrinter::new();
+            pretty_printer
+                .input_from_bytes(bytes)
+                .language(&self.language)
+                .theme(&self.theme)
+                .colored_output(true);
+            return pretty_print(&mut pretty_printer, &mut streams.stdout);
+        }
+        streams.stdout.write_all(bytes)?;
+        Ok(())
+    }
+}
+
+/// Offer the languages and themes bat was built with when completing [`HighlightArgs`]
+#[cfg(feature = "highlight")]
+pub fn complete_highlight_args(command: Command) -> Command {
+    let printer = PrettyPrinter::new();
+    // bat takes either a language's name or one of its file extensions
+    let mut languages: Vec<String> = printer
+        .syntaxes()
+        .flat_map(|syntax| {
+            let name = (!syntax.name.contains(char::is_whitespace)).then_some(syntax.name);
+            name.into_iter().chain(syntax.file_extensions)
+        })
+        .collect();
+    languages.sort();
+    languages.dedup();
+    let themes: Vec<String> = printer.themes().map(str::to_owned).collect();
+
+    command
+        .mut_arg("language", |arg| {
+            arg.value_parser(PossibleValuesParser::new(languages))
+        })
+        .mut_arg("theme", |arg| {
+            arg.value_parser(PossibleValuesParser::new(themes))
+        })
+}
+
+/// Adapts an `io::Write` to the `fmt::Write` that bat's `PrettyPrinter` renders into,
+/// keeping the underlying I/O error around since `fmt::Error` carries none.
+#[cfg(feature = "highlight")]
+struct FmtWriter<'a> {
+    inner: &'a mut dyn Write,
+    error: Option<io::Error>,
+}
+
+#[cfg(feature = "highlight")]
+impl fmt::Write for FmtWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.inner.write_all(s.as_bytes()).map_err(|e| {
+            self.error = Some(e);
+            fmt::Error
+        })
+    }
+}
+
+/// Render the inputs of `printer` into `out` instead of the process' stdout
+#[cfg(feature = "highlight")]
+pub fn pretty_print(printer: &mut PrettyPrinter, out: &mut dyn Write) -> anyhow::Result<()> {
+    let mut writer = FmtWriter {
+        inner: out,
+        error: None,
+    };
+    let result = printer.print_with_writer(Some(&mut writer));
+    if let Some(e) = writer.error {
+        return Err(e.into());
+    }
+    result?;
+    Ok(())
+}
@@ -0,0 +1,153 @@
+use std::io::Write;
+
+use anyhow::Result;
+use clap::{ArgMatches, Command, CommandFactory, FromArgMatches, Parser};
+use serde::Serialize;
+
+use super::{Streams, write_json};
+use crate::applet::Applet;
+
+const MIN_CORES_ALLOWED: u8 = 1;
+
+/// Print the number of cores available to the current process
+#[derive(Parser)]
+pub struct NprocArgs {
+    /// print the number of cores available to the system
+    #[arg(long, short)]
+    pub all: bool,
+
+    /// ignore up to N cores
+    #[arg(
+        long,
+        short,
+        value_name = "N",
+        hide_default_value = true,
+        default_value_t = 0
+    )]
+    pub ignore: usize,
+
+    /// maximum threads to report
+    #[arg(long, env = "OMP_NUM_LIMIT")]
+    pub omp_num_limit: Option<usize>,
+
+    /// minimum threads to report
+    #[arg(long, env = "OMP_NUM_THREADS")]
+    pub omp_num_threads: Option<usize>,
+
+    /// print the count and what limited it as JSON, for scripts
+    #[arg(long)]
+    pub json: bool,
+}
+
+pub struct Nproc;
+
+impl Applet for Nproc {
+    fn name(&self) -> &'static str {
+        "nproc"
+    }
+
+    fn command(&self) -> Command {
+        NprocArgs::command()
+    }
+
+    fn run(&self, matches: &ArgMatches, streams: &mut Streams) -> Result<i32> {
+        let args = NprocArgs::from_arg_matches(matches)?;
+        nproc_command(&args, &mut streams.stdout)?;
+        Ok(0)
+    }
+}
+
+/// Where the number of cores `nproc` reports comes from
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CoreSource {
+    /// the physical cores of the system, for `--all`
+    Physical,
+    /// the cores the process may run on, as limited by its affinity and cgroup quota
+    Available,
+    /// `OMP_NUM_THREADS`, which overrides the rest
+    OmpNumThreads,
+    /// `OMP_NUM_LIMIT`, where it is lower than the available cores
+    OmpNumLimit,
+}
+
+/// The number of cores `nproc` reports, as `nproc --json` prints it
+#[derive(Debug, Serialize)]
+pub struct CoreCount {
+    pub cores: usize,
+    pub source: CoreSource,
+    /// how many cores `--ignore` took off
+    pub ignored: usize,
+}
+
+/// Count the cores as `nproc` would with `args`
+pub fn count_cores(args: &NprocArgs) -> CoreCount {
+    let (cores, source) = if args.all {
+        (num_cpus::get_physical(), CoreSource::Physical)
+    } else if let Some(threads) = args.omp_num_threads {
+        // skip the check if OMP_NUM_THREADS is set
+        (threads, CoreSource::OmpNumThreads)
+    } else if let Some(limit) = args.omp_num_limit {
+        // OMP_NUM_LIMIT is applied only if less than sys_cores
+        let sys_cores = num_cpus::get();
+        if limit < sys_cores {
+            (limit, CoreSource::OmpNumLimit)
+        } else {
+            (sys_cores, CoreSource::Available)
+        }
+    } else {
+        (num_cpus::get(), CoreSource::Available)
+    };
+
+    CoreCount {
+        // never report fewer than the minimum, however many are ignored
+        cores: cores
+            .saturating_sub(args.ignore)
+            .max(usize::from(MIN_CORES_ALLOWED)),
+        source,
+        ignored: args.ignore,
+    }
+}
+
+pub fn nproc_command(args: &NprocArgs, out: &mut impl Write) -> Result<()> {
+    let count = count_cores(args);
+    if args.json {
+        return write_json(&count, out);
+    }
+    writeln!(out, "{}", count.cores)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_cmd::{Command, cargo::cargo_bin, prelude::*};
+
+    #[test]
+    fn json_names_the_limiting_source() {
+        // Arrange
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.args(["nproc", "--json", "--ignore", "1"])
+            .env("OMP_NUM_THREADS", "3");
+
+        // Assert
+        cmd.assert().success().stdout(
+            "{\n  \"cores\": 2,\n  \"source\": \"omp_num_threads\",\n  \"ignored\": 1\n}\n",
+        );
+    }
+
+    #[test]
+    fn stops_at_closed_pipe() {
+        // Arrange
+        let mut cmd = std::process::Command::new(cargo_bin(env!("CARGO_PKG_NAME")));
+
+        // Act
+        cmd.args(["nproc"]).stdout(crate::closed_pipe());
+
+        // Assert
+        cmd.assert()
+            .code(crate::error::status::BROKEN_PIPE)
+            .stderr("");
+    }
+}
@@ -0,0 +1,267 @@
+use std::{
+    ffi::OsString,
+    fs::{self, File},
+    io,
+    os::{
+        fd::AsFd,
+        unix::{fs::MetadataExt, process::ExitStatusExt},
+    },
+    path::PathBuf,
+    process::Command,
+};
+
+use anyhow::Result;
+use clap::{ArgMatches, Command as ClapCommand, CommandFactory, FromArgMatches, Parser};
+use rustix::thread::{LinkNameSpaceType, move_into_link_name_space};
+
+use super::{Streams, sh::sh_command};
+use crate::{
+    applet::Applet,
+    error::{Error, status},
+};
+
+/// The namespaces nsenter knows, named as they are in `/proc/PID/ns`, in the order
+/// they are entered: the user namespace first, so that the capabilities it grants
+/// apply to the rest
+const NAMESPACES: [(&str, LinkNameSpaceType); 7] = [
+    ("user", LinkNameSpaceType::User),
+    ("cgroup", LinkNameSpaceType::ControlGroup),
+    ("ipc", LinkNameSpaceType::InterProcessCommunication),
+    ("uts", LinkNameSpaceType::HostNameAndNISDomainName),
+    ("net", LinkNameSpaceType::Network),
+    ("pid", LinkNameSpaceType::ProcessID),
+    ("mnt", LinkNameSpaceType::Mount),
+];
+
+/// Run a program in the namespaces of another process
+#[derive(Parser)]
+pub struct NsenterArgs {
+    /// the PID of the process whose namespaces to enter
+    #[arg(long, short)]
+    pub target: u32,
+
+    /// enter the mount namespace
+    #[arg(long, short)]
+    pub mount: bool,
+
+    /// enter the UTS namespace (hostname and domain name)
+    #[arg(long, short)]
+    pub uts: bool,
+
+    /// enter the IPC namespace
+    #[arg(long, short)]
+    pub ipc: bool,
+
+    /// enter the network namespace
+    #[arg(long, short)]
+    pub net: bool,
+
+    /// enter the pid namespace; COMMAND and its children are in it, nsenter itself is not
+    #[arg(long, short)]
+    pub pid: bool,
+
+    /// enter the cgroup namespace
+    #[arg(long, short = 'C')]
+    pub cgroup: bool,
+
+    /// enter the user namespace
+    #[arg(long, short = 'U')]
+    pub user: bool,
+
+    /// enter every namespace of the target that differs from ours
+    #[arg(long, short)]
+    pub all: bool,
+
+    /// the command to run and its arguments; rizzybox's `sh` if not given
+    #[arg(trailing_var_arg = true)]
+    pub command: Vec<OsString>,
+}
+
+pub struct Nsenter;
+
+impl Applet for Nsenter {
+    fn name(&self) -> &'static str {
+        "nsenter"
+    }
+
+    /// the namespaces are changed for the whole process, and `setns` refuses to move a
+    /// multithreaded process into a user namespace
+    fn in_process(&self) -> bool {
+        false
+    }
+
+    fn command(&self) -> ClapCommand {
+        NsenterArgs::command()
+    }
+
+    fn usage_status(&self) -> i32 {
+        status::CANNOT_RUN
+    }
+
+    fn run(&self, matches: &ArgMatches, streams: &mut Streams) -> Result<i32> {
+        let args = NsenterArgs::from_arg_matches(matches)?;
+        nsenter_command(&args, streams)
+    }
+}
+
+pub fn nsenter_command(args: &NsenterArgs, streams: &mut Streams) -> Result<i32> {
+    enter(args.target, &namespaces(args))?;
+
+    let Some((program, program_args)) = args.command.split_first() else {
+        return sh_command(streams);
+    };
+
+    match Command::new(program).args(program_args).status() {
+        // like a shell, report death by a signal as 128 plus its number
+        Ok(exit) => Ok(exit
+            .code()
+            .or_else(|| exit.signal().map(|signal| 128 + signal))
+            .unwrap_or(status::FAILURE)),
+        Err(e) => {
+            let status = if e.kind() == io::ErrorKind::NotFound {
+                status::NOT_FOUND
+            } else {
+                status::CANNOT_INVOKE
+            };
+            Err(Error::io(format!("'{}'", program.display()), &e)
+                .with_status(status)
+                .into())
+        }
+    }
+}
+
+/// The names of the namespaces `args` asks for, in the order they are entered
+pub fn namespaces(args: &NsenterArgs) -> Vec<&'static str> {
+    let wanted = [
+        args.user,
+        args.cgroup,
+        args.ipc,
+        args.uts,
+        args.net,
+        args.pid,
+        args.mount,
+    ];
+    NAMESPACES
+        .iter()
+        .zip(wanted)
+        .filter(|(_, wanted)| args.all || *wanted)
+        .map(|((name, _), _)| *name)
+        .collect()
+}
+
+/// Move this process into the namespaces of `pid` that are named in `names`. Ones it
+/// already shares with `pid` are skipped, since entering the user namespace we are
+/// in is an error. Every namespace is opened before any is entered, because the
+/// mount namespace decides what `/proc` shows.
+pub fn enter(pid: u32, names: &[&str]) -> Result<()> {
+    let mut targets = Vec::new();
+    for &(name, kind) in NAMESPACES.iter().filter(|(name, _)| names.contains(name)) {
+        let path = PathBuf::from(format!("/proc/{pid}/ns/{name}"));
+        let file = File::open(&path).map_err(|e| {
+            Error::io(format!("cannot open '{}'", path.display()), &e)
+                .with_status(status::CANNOT_RUN)
+        })?;
+        if is_current(name, &file) {
+            continue;
+        }
+        targets.push((path, file, kind));
+    }
+
+    for (path, file, kind) in targets {
+        move_into_link_name_space(file.as_fd(), Some(kind)).map_err(|errno| {
+            Error::io(format!("cannot enter '{}'", path.display()), &errno.into())
+                .with_status(status::CANNOT_RUN)
+        })?;
+    }
+    Ok(())
+}
+
+/// Whether `namespace` is the one named `name` that this process is already in
+fn is_current(name: &str, namespace: &File) -> bool {
+    let (Ok(ours), Ok(theirs)) = (
+        fs::metadata(format!("/proc/self/ns/{name}")),
+        namespace.metadata(),
+    ) else {
+        return false;
+    };
+    ours.dev() == theirs.dev() && ours.ino() == theirs.ino()
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_cmd::Command;
+    use std::{
+        io::{BufRead, BufReader},
+        process::{self, Stdio},
+    };
+
+    use super::{NsenterArgs, namespaces};
+
+    #[test]
+    fn enters_the_uts_namespace_of_a_target() {
+        // Arrange
+        let mut target = process::Command::new("unshare")
+            .args(["--user", "--map-root-user", "--uts", "sh", "-c"])
+            .arg("echo rizzybox-ns > /proc/sys/kernel/hostname && echo ready && exec sleep 30")
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .unwrap();
+        let mut ready = String::new();
+        let _ = BufReader::new(target.stdout.take().unwrap()).read_line(&mut ready);
+        if ready != "ready\n" {
+            // unshare is missing, or unprivileged user namespaces are disabled here
+            let _ = target.kill();
+            let _ = target.wait();
+            return;
+        }
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.args(["nsenter", "-t", &target.id().to_string(), "-U", "-u"])
+            .args(["cat", "/proc/sys/kernel/hostname"]);
+
+        // Assert
+        let assert = cmd.assert();
+        target.kill().unwrap();
+        target.wait().unwrap();
+        assert.success().stdout("rizzybox-ns\n");
+    }
+
+    #[test]
+    fn reports_a_missing_target() {
+        // Arrange
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.args(["nsenter", "-t", "999999999", "-n", "true"]);
+
+        // Assert
+        cmd.assert().code(125).stderr(predicates::str::ends_with(
+            "nsenter: cannot open '/proc/999999999/ns/net': No such file or directory\n",
+        ));
+    }
+
+    #[test]
+    fn enters_user_namespace_first() {
+        // Arrange
+        let args = NsenterArgs {
+            target: 1,
+            mount: true,
+            uts: false,
+            ipc: false,
+            net: true,
+            pid: false,
+            cgroup: false,
+            user: true,
+            all: false,
+            command: Vec::new(),
+        };
+
+        // Act
+        let names = namespaces(&args);
+
+        // Assert
+        assert_eq!(names, ["user", "net", "mnt"]);
+    }
+}
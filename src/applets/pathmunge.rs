@@ -0,0 +1,197 @@
+use std::io::{BufRead, BufReader, Read, Write};
+
+use anyhow::Result;
+use clap::{
+    ArgMatches, Command, CommandFactory, FromArgMatches, Parser, Subcommand,
+    builder::PossibleValuesParser,
+};
+
+use super::Streams;
+use crate::applet::Applet;
+
+/// Insert a path into $PATH, only if it isn't already there
+#[derive(Parser)]
+pub struct PathmungeArgs {
+    #[command(subcommand)]
+    pub command: PathmungeCommand,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum PathmungeCommand {
+    /// Insert value at the end of $PATH
+    After {
+        /// The value to add to $PATH
+        path: String,
+
+        /// If value is found in $PATH, move it to the end
+        #[arg(long, short, alias = "move")]
+        force: bool,
+    },
+
+    /// Insert value at the beginning of $PATH
+    Before {
+        /// The value to add to $PATH
+        path: String,
+
+        /// If value is found in $PATH, move it to the beginning
+        #[arg(long, short, alias = "move")]
+        force: bool,
+    },
+    Delete {
+        /// The value to delete from $PATH
+        path: String,
+    },
+}
+
+pub struct Pathmunge;
+
+impl Applet for Pathmunge {
+    fn name(&self) -> &'static str {
+        "pathmunge"
+    }
+
+    fn command(&self) -> Command {
+        PathmungeArgs::command()
+    }
+
+    fn complete(&self, command: Command) -> Command {
+        let entries: Vec<String> = std::env::var("PATH")
+            .unwrap_or_default()
+            .split(':')
+            .filter(|entry| !entry.is_empty())
+            .map(str::to_owned)
+            .collect();
+        command.mut_subcommand("delete", |delete| {
+            delete.mut_arg("path", |arg| {
+                arg.value_parser(PossibleValuesParser::new(entries))
+            })
+        })
+    }
+
+    fn run(&self, matches: &ArgMatches, streams: &mut Streams) -> Result<i32> {
+        let args = PathmungeArgs::from_arg_matches(matches)?;
+        // `$PATH` is piped in, or taken from the environment
+        let path_env = if streams.stdin_is_terminal {
+            PathEnv::from_env()
+        } else {
+            PathEnv::read(&mut streams.stdin)?
+        };
+        pathmunge_command(args.command, path_env, &mut streams.stdout)?;
+        Ok(0)
+    }
+}
+
+/// The entries of a `$PATH`-style list
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PathEnv {
+    pub paths: Vec<String>,
+}
+
+impl std::fmt::Display for PathEnv {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.paths.join(":"))
+    }
+}
+
+impl PathEnv {
+    /// Split a `$PATH`-style list on `:`
+    pub fn parse(path: &str) -> Self {
+        Self {
+            paths: path.trim().split(':').map(str::to_owned).collect(),
+        }
+    }
+
+    /// The `$PATH` of this process, or a sensible default
+    pub fn from_env() -> Self {
+        Self::parse(&std::env::var("PATH").unwrap_or("/bin:/sbin:/usr/bin:/usr/sbin".to_owned()))
+    }
+
+    /// Read a `$PATH`-style list from the first line of `input`
+    pub fn read(input: impl Read) -> Result<Self> {
+        let mut path = String::new();
+        BufReader::new(input).read_line(&mut path)?;
+        Ok(Self::parse(&path))
+    }
+
+    /// Insert or delete a path as `command` says
+    pub fn apply(&mut self, command: PathmungeCommand) {
+        match command {
+            PathmungeCommand::After { path: upath, force } => {
+                if self.paths.contains(&upath) && !upath.is_empty() {
+                    if force {
+                        self.paths.retain(|path| path != &upath);
+                        self.paths.push(upath);
+                    }
+                } else {
+                    self.paths.push(upath);
+                }
+            }
+            PathmungeCommand::Before { path: upath, force } => {
+                if self.paths.contains(&upath) && !upath.is_empty() {
+                    if force {
+                        self.paths.retain(|path| path != &upath);
+                        self.paths.insert(0, upath);
+                    }
+                } else {
+                    self.paths.insert(0, upath);
+                }
+            }
+            PathmungeCommand::Delete { path: upath } => {
+                self.paths.retain(|path| path != &upath);
+            }
+        }
+    }
+}
+
+pub fn pathmunge_command(
+    command: PathmungeCommand,
+    mut path_env: PathEnv,
+    out: &mut impl Write,
+) -> Result<()> {
+    path_env.apply(command);
+    writeln!(out, "{path_env}")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PathEnv, PathmungeCommand};
+    use assert_cmd::{cargo::cargo_bin, prelude::*};
+
+    #[test]
+    fn moves_paths_in_process() {
+        // Arrange
+        let mut path_env = PathEnv::parse("/bin:/usr/local/bin:/usr/bin\n");
+
+        // Act
+        path_env.apply(PathmungeCommand::Before {
+            path: "/usr/local/bin".to_owned(),
+            force: true,
+        });
+        path_env.apply(PathmungeCommand::After {
+            path: "/opt/bin".to_owned(),
+            force: false,
+        });
+
+        // Assert
+        assert_eq!(
+            path_env.to_string(),
+            "/usr/local/bin:/bin:/usr/bin:/opt/bin"
+        );
+    }
+
+    #[test]
+    fn stops_at_closed_pipe() {
+        // Arrange
+        let mut cmd = std::process::Command::new(cargo_bin(env!("CARGO_PKG_NAME")));
+
+        // Act
+        cmd.args(["pathmunge", "after", "/nonexistent"])
+            .stdout(crate::closed_pipe());
+
+        // Assert
+        cmd.assert()
+            .code(crate::error::status::BROKEN_PIPE)
+            .stderr("");
+    }
+}
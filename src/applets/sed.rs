@@ -0,0 +1,1475 @@
+use std::{
+    borrow::Cow,
+    ffi::OsString,
+    fs::{self, File, OpenOptions},
+    io::{self, BufRead, BufReader, BufWriter, ErrorKind, Read, Write},
+    os::unix::{
+        ffi::OsStrExt,
+        fs::{MetadataExt, fchown},
+    },
+    path::{Path, PathBuf},
+};
+
+use anyhow::Result;
+use clap::{ArgMatches, Command as ClapCommand, CommandFactory, FromArgMatches, Parser};
+use fancy_regex::{Captures, Regex, RegexBuilder};
+
+use super::Streams;
+use crate::{
+    applet::Applet,
+    error::{self, Error, status},
+    regex::translate,
+};
+
+/// Stream editor for filtering and transforming text
+#[derive(Parser)]
+pub struct SedArgs {
+    /// the SCRIPT to run, unless given with -e or -f
+    #[arg(required_unless_present_any = ["expression", "file"])]
+    pub script: Option<OsString>,
+
+    /// files to edit; stdin if none are given
+    pub input: Vec<OsString>,
+
+    /// add SCRIPT to the commands to run
+    #[arg(long, short, value_name = "SCRIPT")]
+    pub expression: Vec<OsString>,
+
+    /// add the contents of FILE to the commands to run
+    #[arg(long, short, value_name = "FILE")]
+    pub file: Vec<PathBuf>,
+
+    /// don't print the pattern space at the end of each cycle
+    #[arg(long, short = 'n', visible_alias = "silent")]
+    pub quiet: bool,
+
+    /// use extended regular expressions
+    #[arg(long, short = 'E', visible_short_alias = 'r')]
+    pub regexp_extended: bool,
+
+    /// edit files in place, keeping a backup named with SUFFIX if given. A `*` in
+    /// SUFFIX stands for the file's name, e.g. `-i'bak/*'`
+    #[arg(
+        long,
+        short,
+        value_name = "SUFFIX",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = ""
+    )]
+    pub in_place: Option<String>,
+}
+
+pub struct Sed;
+
+impl Applet for Sed {
+    fn name(&self) -> &'static str {
+        "sed"
+    }
+
+    fn command(&self) -> ClapCommand {
+        SedArgs::command()
+    }
+
+    /// Turn `-i[SUFFIX]` into `--in-place[=SUFFIX]`, since a suffix has to be attached
+    /// to `-i` and clap would take the script after a bare `-i` for one
+    fn rewrite_args(&self, args: &mut Vec<OsString>, at: usize) {
+        let mut i = at + 1;
+        while let Some(arg) = args.get(i).and_then(|arg| arg.to_str()) {
+            if arg == "--" {
+                break;
+            }
+            let Some(flags) = arg
+                .strip_prefix('-')
+                .filter(|flags| !flags.starts_with('-'))
+            else {
+                // the script or file of `--expression` and `--file` may look like `-i`
+                i += if arg == "--expression" || arg == "--file" {
+                    2
+                } else {
+                    1
+                };
+                continue;
+            };
+            // `-e` and `-f` take the rest of their cluster, or the next argument
+            match flags.find(['e', 'f', 'i']) {
+                Some(at) if flags[at..].starts_with('i') => {
+                    let suffix = &flags[at + 1..];
+                    let in_place = if suffix.is_empty() {
+                        "--in-place".to_owned()
+                    } else {
+                        format!("--in-place={suffix}")
+                    };
+                    let mut replacement = vec![OsString::from(in_place)];
+                    if at > 0 {
+                        replacement.insert(0, format!("-{}", &flags[..at]).into());
+                    }
+                    let count = replacement.len();
+                    args.splice(i..=i, replacement);
+                    i += count;
+                }
+                Some(at) if at == flags.len() - 1 => i += 2,
+                _ => i += 1,
+            }
+        }
+    }
+
+    fn run(&self, matches: &ArgMatches, streams: &mut Streams) -> Result<i32> {
+        let args = SedArgs::from_arg_matches(matches)?;
+        sed_command(
+            &args,
+            &mut streams.stdin,
+            &mut streams.stdout,
+            &mut streams.stderr,
+        )
+    }
+}
+
+/// Run the script in `args` over its input files, or `input` if there are none,
+/// writing the result to `out` or, with `--in-place`, back to the files. Files that
+/// can't be read are reported to `err` and skipped, returning 2 at the end; `q`
+/// may exit with a status of its own.
+pub fn sed_command(
+    args: &SedArgs,
+    input: &mut dyn Read,
+    out: &mut dyn Write,
+    err: &mut dyn Write,
+) -> Result<i32> {
+    let mut pieces = Vec::new();
+    let mut files = args.input.clone();
+    match &args.script {
+        Some(script) if args.expression.is_empty() && args.file.is_empty() => {
+            pieces.push((
+                "-e expression #1".to_owned(),
+                decode(script.as_bytes()).into(),
+            ));
+        }
+        // with -e or -f, what clap took for the script is the first input file
+        first_file => {
+            files.splice(0..0, first_file.iter().cloned());
+            for (i, expression) in args.expression.iter().enumerate() {
+                let expression = decode(expression.as_bytes()).into();
+                pieces.push((format!("-e expression #{}", i + 1), expression));
+            }
+            for file in &args.file {
+                let script = fs::read(file)
+                    .map_err(|e| Error::io(format!("couldn't open file {}", file.display()), &e))?;
+                pieces.push((format!("file {}", file.display()), decode(&script).into()));
+            }
+        }
+    }
+    let script = Script::parse(&pieces, args.regexp_extended)?;
+    let mut editor = Editor::new(script, args.quiet);
+
+    let Some(suffix) = &args.in_place else {
+        if files.is_empty() {
+            files.push("-".into());
+        }
+        let mut input = Input::new(files, Some(input), err)?;
+        let quit = editor.run(&mut input, out)?;
+        return Ok(quit.unwrap_or(if input.failed { status::TROUBLE } else { 0 }));
+    };
+
+    let mut failed = false;
+    for file in &files {
+        match edit_in_place(&mut editor, Path::new(file), suffix, err)? {
+            InPlace::Edited(Some(code)) => return Ok(code),
+            InPlace::Edited(None) => {}
+            InPlace::Failed => failed = true,
+        }
+    }
+    Ok(if failed { status::TROUBLE } else { 0 })
+}
+
+enum InPlace {
+    /// the file was edited, and the script quit with the status, if it did
+    Edited(Option<i32>),
+    Failed,
+}
+
+/// Run `editor` over `path`, replacing it with the output through a rename so that
+/// readers see either the old contents or the new. With a `suffix`, the old
+/// contents are kept as a backup.
+fn edit_in_place(
+    editor: &mut Editor,
+    path: &Path,
+    suffix: &str,
+    err: &mut dyn Write,
+) -> Result<InPlace> {
+    let report = |err: &mut dyn Write, target: String, e: &io::Error| -> Result<InPlace> {
+        writeln!(err, "sed: {}", Error::io(target, e))?;
+        Ok(InPlace::Failed)
+    };
+    let metadata = match fs::metadata(path) {
+        Ok(metadata) if metadata.is_file() => metadata,
+        Ok(_) => {
+            writeln!(
+                err,
+                "sed: couldn't edit {}: not a regular file",
+                path.display()
+            )?;
+            return Ok(InPlace::Failed);
+        }
+        Err(e) => return report(err, format!("can't read {}", path.display()), &e),
+    };
+
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let (temp, mut output) = match create_temp(path, &name) {
+        Ok((temp, file)) => (temp, BufWriter::new(file)),
+        Err(e) => {
+            let target = format!("couldn't open temporary file for {}", path.display());
+            return report(err, target, &e);
+        }
+    };
+
+    editor.reset();
+    let edited = {
+        let mut input = Input::new(vec![path.as_os_str().to_owned()], None, &mut *err)?;
+        editor.run(&mut input, &mut output).and_then(|quit| {
+            output.flush()?;
+            let file = output.get_ref();
+            // like GNU sed, keep at least the group when the owner can't be kept
+            if fchown(file, Some(metadata.uid()), Some(metadata.gid())).is_err() {
+                let _ = fchown(file, None, Some(metadata.gid()));
+            }
+            file.set_permissions(metadata.permissions())?;
+            Ok((quit, input.failed))
+        })
+    };
+    let quit = match edited {
+        Ok((quit, false)) => quit,
+        Ok((_, true)) => {
+            let _ = fs::remove_file(&temp);
+            return Ok(InPlace::Failed);
+        }
+        Err(e) => {
+            let _ = fs::remove_file(&temp);
+            return Err(e);
+        }
+    };
+
+    if !suffix.is_empty() {
+        let backup = if suffix.contains('*') {
+            path.with_file_name(suffix.replace('*', &name))
+        } else {
+            path.with_file_name(format!("{name}{suffix}"))
+        };
+        let _ = fs::remove_file(&backup);
+        if let Err(e) = fs::hard_link(path, &backup).or_else(|_| fs::copy(path, &backup).map(drop))
+        {
+            let _ = fs::remove_file(&temp);
+            return report(err, format!("couldn't back up {}", path.display()), &e);
+        }
+    }
+    if let Err(e) = fs::rename(&temp, path) {
+        let _ = fs::remove_file(&temp);
+        return report(err, format!("couldn't replace {}", path.display()), &e);
+    }
+    Ok(InPlace::Edited(quit))
+}
+
+/// Create a file next to `path` to write its new contents to. The file must not
+/// exist yet, so that a link planted under its name can't redirect the write.
+fn create_temp(path: &Path, name: &str) -> io::Result<(PathBuf, File)> {
+    let mut attempt = 0;
+    loop {
+        let temp = path.with_file_name(format!(".{name}.sed-{}-{attempt}", std::process::id()));
+        match OpenOptions::new().write(true).create_new(true).open(&temp) {
+            Ok(file) => return Ok((temp, file)),
+            Err(e) if e.kind() == ErrorKind::AlreadyExists && attempt < 100 => attempt += 1,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// The lines of the input files, read one ahead so that `$` knows the last one
+struct Input<'a> {
+    files: std::vec::IntoIter<OsString>,
+    stdin: Option<&'a mut dyn Read>,
+    reader: Option<Box<dyn BufRead + 'a>>,
+    next: Option<Line>,
+    err: &'a mut dyn Write,
+    /// whether a file could not be read
+    failed: bool,
+}
+
+/// A line of input, as the bytes it was read as
+struct Line {
+    text: Vec<u8>,
+    /// whether the line ended in a newline, which only the last one may lack
+    newline: bool,
+}
+
+impl<'a> Input<'a> {
+    fn new(
+        files: Vec<OsString>,
+        stdin: Option<&'a mut dyn Read>,
+        err: &'a mut dyn Write,
+    ) -> Result<Self> {
+        let mut input = Self {
+            files: files.into_iter(),
+            stdin,
+            reader: None,
+            next: None,
+            err,
+            failed: false,
+        };
+        input.read_ahead()?;
+        Ok(input)
+    }
+
+    fn next_line(&mut self) -> Result<Option<Line>> {
+        let line = self.next.take();
+        if line.is_some() {
+            self.read_ahead()?;
+        }
+        Ok(line)
+    }
+
+    fn is_last(&self) -> bool {
+        self.next.is_none()
+    }
+
+    /// Read the line after the current one, opening the next file as needed
+    fn read_ahead(&mut self) -> Result<()> {
+        loop {
+            let Some(reader) = &mut self.reader else {
+                let Some(file) = self.files.next() else {
+                    return Ok(());
+                };
+                if file == "-" {
+                    if let Some(stdin) = self.stdin.take() {
+                        self.reader = Some(Box::new(BufReader::new(stdin)));
+                    }
+                    continue;
+                }
+                match File::open(&file) {
+                    Ok(file) => self.reader = Some(Box::new(BufReader::new(file))),
+                    Err(e) => {
+                        let target = format!("can't read {}", Path::new(&file).display());
+                        writeln!(self.err, "sed: {}", Error::io(target, &e))?;
+                        self.failed = true;
+                    }
+                }
+                continue;
+            };
+
+            let mut bytes = Vec::new();
+            let read = match reader.read_until(b'\n', &mut bytes) {
+                Ok(read) => read,
+                Err(e) if error::is_write_error(&e) => return Err(e.into()),
+                Err(e) => {
+                    writeln!(self.err, "sed: {}", Error::io("read error", &e))?;
+                    self.failed = true;
+                    0
+                }
+            };
+            if read == 0 {
+                self.reader = None;
+                continue;
+            }
+            let newline = bytes.last() == Some(&b'\n');
+            if newline {
+                bytes.pop();
+            }
+            self.next = Some(Line {
+                text: bytes,
+                newline,
+            });
+            return Ok(());
+        }
+    }
+}
+
+/// A parsed script: its commands in order, with blocks flattened into jumps, and
+/// the regexes they refer to by index
+pub struct Script {
+    commands: Vec<Command>,
+    regexes: Vec<Regex>,
+}
+
+struct Command {
+    first: Option<Address>,
+    last: Option<Address>,
+    negated: bool,
+    /// whether the range from `first` to `last` has started and not yet ended
+    in_range: bool,
+    kind: Kind,
+}
+
+#[derive(Clone, Copy)]
+enum Address {
+    Line(u64),
+    LastLine,
+    /// the index of a regex, or `None` for the last one used
+    Regex(Option<usize>),
+}
+
+enum Kind {
+    /// `{`, with the index of the command after its `}`
+    Block(usize),
+    EndBlock,
+    Substitute(Substitute),
+    Transliterate(Vec<(char, char)>),
+    Append(Vec<u8>),
+    Insert(Vec<u8>),
+    Change(Vec<u8>),
+    Delete,
+    Print,
+    LineNumber,
+    Next,
+    NextAppend,
+    Hold,
+    HoldAppend,
+    Get,
+    GetAppend,
+    Exchange,
+    Quit(i32),
+}
+
+struct Substitute {
+    regex: Option<usize>,
+    replacement: Vec<Replacement>,
+    global: bool,
+    /// replace only the match with this number, counting from 1
+    occurrence: usize,
+    print: bool,
+}
+
+enum Replacement {
+    /// text as [`decode`] gives it, like the pattern space it goes into
+    Text(String),
+    /// a group of the match, where 0 (`&`) is the whole of it
+    Group(usize),
+}
+
+impl Script {
+    /// Parse the `pieces` of a script, given as `-e` and `-f`, each with the name
+    /// to report errors in it by. They are joined with newlines, so text for `a`,
+    /// `i` and `c` may continue from one into the next.
+    pub fn parse(pieces: &[(String, String)], extended: bool) -> Result<Self> {
+        let mut chars = Vec::new();
+        let mut starts = Vec::new();
+        for (name, piece) in pieces {
+            starts.push((chars.len(), name.as_str()));
+            chars.extend(piece.chars());
+            chars.push('\n');
+        }
+        let mut parser = ScriptParser {
+            chars: &chars,
+            pos: 0,
+            extended,
+            script: Script {
+                commands: Vec::new(),
+                regexes: Vec::new(),
+            },
+            blocks: Vec::new(),
+        };
+        match parser.parse() {
+            Ok(()) => Ok(parser.script),
+            Err(message) => {
+                let (start, name) = starts
+                    .iter()
+                    .rev()
+                    .find(|(start, _)| *start < parser.pos.max(1))
+                    .copied()
+                    .unwrap_or((0, "-e expression #1"));
+                let end = starts
+                    .iter()
+                    .find(|(next, _)| *next > start)
+                    .map_or(chars.len(), |(next, _)| *next);
+                // the newline joining the pieces isn't part of either
+                let at = parser.pos.min(end - 1) - start;
+                Err(Error::new(format!("{name}, char {at}: {message}")).into())
+            }
+        }
+    }
+}
+
+struct ScriptParser<'a> {
+    chars: &'a [char],
+    pos: usize,
+    extended: bool,
+    script: Script,
+    /// the indices of the `{` that are still open
+    blocks: Vec<usize>,
+}
+
+impl ScriptParser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek();
+        self.pos += 1;
+        c
+    }
+
+    fn skip_blanks(&mut self) {
+        while self.peek().is_some_and(|c| c == ' ' || c == '\t') {
+            self.pos += 1;
+        }
+    }
+
+    fn parse(&mut self) -> Result<(), String> {
+        loop {
+            while self.peek().is_some_and(|c| c.is_whitespace() || c == ';') {
+                self.pos += 1;
+            }
+            let Some(c) = self.peek() else {
+                break;
+            };
+            if c == '#' {
+                while self.next().is_some_and(|c| c != '\n') {}
+                continue;
+            }
+
+            let first = self.address()?;
+            let mut last = None;
+            if first.is_some() && self.peek() == Some(',') {
+                self.pos += 1;
+                self.skip_blanks();
+                last = Some(self.address()?.ok_or("unexpected `,'")?);
+            }
+            self.skip_blanks();
+            let mut negated = false;
+            while self.peek() == Some('!') {
+                negated = true;
+                self.pos += 1;
+                self.skip_blanks();
+            }
+
+            let command = self.next().ok_or("missing command")?;
+            let kind = self.command(command)?;
+            if matches!(kind, Kind::EndBlock) && (first.is_some() || negated) {
+                return Err("} doesn't want any addresses".to_owned());
+            }
+            self.script.commands.push(Command {
+                first,
+                last,
+                negated,
+                in_range: false,
+                kind,
+            });
+        }
+        if !self.blocks.is_empty() {
+            return Err("unmatched `{'".to_owned());
+        }
+        Ok(())
+    }
+
+    fn address(&mut self) -> Result<Option<Address>, String> {
+        match self.peek() {
+            Some('0'..='9') => {
+                let start = self.pos;
+                while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+                    self.pos += 1;
+                }
+                let number: String = self.chars[start..self.pos].iter().collect();
+                let number = number.parse().map_err(|_| "invalid line number")?;
+                if number == 0 {
+                    return Err("invalid usage of line address 0".to_owned());
+                }
+                Ok(Some(Address::Line(number)))
+            }
+            Some('$') => {
+                self.pos += 1;
+                Ok(Some(Address::LastLine))
+            }
+            Some(delimiter @ ('/' | '\\')) => {
+                self.pos += 1;
+                let delimiter = if delimiter == '\\' {
+                    self.next().ok_or("unterminated address regex")?
+                } else {
+                    delimiter
+                };
+                let pattern = self.delimited(delimiter, "unterminated address regex")?;
+                let ignore_case = self.peek() == Some('I');
+                if ignore_case {
+                    self.pos += 1;
+                }
+                Ok(Some(Address::Regex(self.regex(&pattern, ignore_case)?)))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// The text up to the next unescaped `delimiter`, with the backslash taken off
+    /// an escaped delimiter and `\n` standing for a newline
+    fn delimited(&mut self, delimiter: char, unterminated: &str) -> Result<String, String> {
+        let mut text = String::new();
+        loop {
+            match self.next() {
+                None => return Err(unterminated.to_owned()),
+                Some(c) if c == delimiter => return Ok(text),
+                Some('\\') => match self.next() {
+                    None => return Err(unterminated.to_owned()),
+                    Some(c) if c == delimiter => text.push(c),
+                    Some('\n') => text.push('\n'),
+                    Some(c) => {
+                        text.push('\\');
+                        text.push(c);
+                    }
+                },
+                Some(c) => text.push(c),
+            }
+        }
+    }
+
+    /// Compile `pattern` and add it to the script, or `None` if it is empty, which
+    /// stands for the last regex used
+    fn regex(&mut self, pattern: &str, ignore_case: bool) -> Result<Option<usize>, String> {
+        if pattern.is_empty() {
+            return Ok(None);
+        }
+        let regex = RegexBuilder::new(&translate(pattern, self.extended))
+            .case_insensitive(ignore_case)
+            .build()
+            .map_err(|e| e.to_string())?;
+        self.script.regexes.push(regex);
+        Ok(Some(self.script.regexes.len() - 1))
+    }
+
+    fn command(&mut self, command: char) -> Result<Kind, String> {
+        let kind = match command {
+            '{' => {
+                self.blocks.push(self.script.commands.len());
+                return Ok(Kind::Block(0));
+            }
+            '}' => {
+                let start = self.blocks.pop().ok_or("unexpected `}'")?;
+                self.script.commands[start].kind = Kind::Block(self.script.commands.len() + 1);
+                Kind::EndBlock
+            }
+            's' => Kind::Substitute(self.substitute()?),
+            'y' => {
+                let delimiter = self.next().ok_or("unterminated `y' command")?;
+                let from = self.delimited(delimiter, "unterminated `y' command")?;
+                let to = self.delimited(delimiter, "unterminated `y' command")?;
+                let (from, to) = (unescape(&from), unescape(&to));
+                if from.chars().count() != to.chars().count() {
+                    return Err("strings for `y' command are different lengths".to_owned());
+                }
+                Kind::Transliterate(from.chars().zip(to.chars()).collect())
+            }
+            'a' | 'i' | 'c' => {
+                let text = encode(&self.text()?).into_owned();
+                return Ok(match command {
+                    'a' => Kind::Append(text),
+                    'i' => Kind::Insert(text),
+                    _ => Kind::Change(text),
+                });
+            }
+            'q' => {
+                self.skip_blanks();
+                let start = self.pos;
+                while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+                    self.pos += 1;
+                }
+                let code: String = self.chars[start..self.pos].iter().collect();
+                Kind::Quit(code.parse().unwrap_or(0))
+            }
+            'd' => Kind::Delete,
+            'p' => Kind::Print,
+            '=' => Kind::LineNumber,
+            'n' => Kind::Next,
+            'N' => Kind::NextAppend,
+            'h' => Kind::Hold,
+            'H' => Kind::HoldAppend,
+            'g' => Kind::Get,
+            'G' => Kind::GetAppend,
+            'x' => Kind::Exchange,
+            ':' | 'b' | 't' | 'D' | 'P' | 'r' | 'w' | 'l' | 'e' | 'F' | 'z' | 'Q' | 'T' | 'v'
+            | 'W' | 'R' => {
+                return Err(format!("unsupported command: `{command}'"));
+            }
+            _ => return Err(format!("unknown command: `{command}'")),
+        };
+        self.end_of_command()?;
+        Ok(kind)
+    }
+
+    /// After a command, only blanks may come before a `;`, newline, `}` or comment
+    fn end_of_command(&mut self) -> Result<(), String> {
+        self.skip_blanks();
+        match self.peek() {
+            None | Some(';' | '\n' | '}' | '#') => Ok(()),
+            Some(c) => Err(format!("extra characters after command: `{c}'")),
+        }
+    }
+
+    /// The `s` command, after the `s`
+    fn substitute(&mut self) -> Result<Substitute, String> {
+        let delimiter = self.next().ok_or("unterminated `s' command")?;
+        if delimiter == '\n' || delimiter == '\\' {
+            return Err("unterminated `s' command".to_owned());
+        }
+        let pattern = self.delimited(delimiter, "unterminated `s' command")?;
+        let replacement = self.replacement(delimiter)?;
+
+        let mut global = false;
+        let mut print = false;
+        let mut ignore_case = false;
+        let mut occurrence = None;
+        while let Some(c) = self.peek() {
+            match c {
+                'g' => global = true,
+                'p' => print = true,
+                'i' | 'I' => ignore_case = true,
+                '0'..='9' => {
+                    let start = self.pos;
+                    while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+                        self.pos += 1;
+                    }
+                    let number: String = self.chars[start..self.pos].iter().collect();
+                    let number = number.parse().ok().filter(|&n| n > 0);
+                    occurrence =
+                        Some(number.ok_or("number option to `s' command may not be zero")?);
+                    continue;
+                }
+                _ => break,
+            }
+            self.pos += 1;
+        }
+        Ok(Substitute {
+            regex: self.regex(&pattern, ignore_case)?,
+            replacement,
+            global,
+            occurrence: occurrence.unwrap_or(1),
+            print,
+        })
+    }
+
+    fn replacement(&mut self, delimiter: char) -> Result<Vec<Replacement>, String> {
+        let mut parts = Vec::new();
+        let mut text = String::new();
+        loop {
+            let group = match self.next() {
+                None => return Err("unterminated `s' command".to_owned()),
+                Some(c) if c == delimiter => break,
+                Some('&') => 0,
+                Some('\\') => match self.next() {
+                    None => return Err("unterminated `s' command".to_owned()),
+                    Some(digit @ '0'..='9') => digit as usize - '0' as usize,
+                    Some('n') => {
+                        text.push('\n');
+                        continue;
+                    }
+                    Some('t') => {
+                        text.push('\t');
+                        continue;
+                    }
+                    Some(c) => {
+                        text.push(c);
+                        continue;
+                    }
+                },
+                Some(c) => {
+                    text.push(c);
+                    continue;
+                }
+            };
+            if !text.is_empty() {
+                parts.push(Replacement::Text(std::mem::take(&mut text)));
+            }
+            parts.push(Replacement::Group(group));
+        }
+        if !text.is_empty() {
+            parts.push(Replacement::Text(text));
+        }
+        Ok(parts)
+    }
+
+    /// The text of `a`, `i` or `c`, either on the same line (`a text`) or on the
+    /// lines after a backslash, which go on for as long as they end in another
+    fn text(&mut self) -> Result<String, String> {
+        self.skip_blanks();
+        if self.peek() == Some('\\') {
+            self.pos += 1;
+            self.skip_blanks();
+            if self.peek() == Some('\n') {
+                self.pos += 1;
+            }
+        }
+        let mut text = String::new();
+        while let Some(c) = self.next() {
+            match c {
+                '\n' => break,
+                '\\' => text.extend(self.next()),
+                c => text.push(c),
+            }
+        }
+        if text.is_empty() {
+            return Err("expected \\ after `a', `c' or `i'".to_owned());
+        }
+        Ok(text)
+    }
+}
+
+/// `text` with `\n` as a newline and the backslash taken off other escapes
+fn unescape(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some(c) => unescaped.push(c),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+/// What to do after a command
+enum Flow {
+    Continue,
+    /// end the cycle, printing the pattern space unless `-n` or it was deleted
+    EndCycle {
+        print: bool,
+    },
+    Quit(i32),
+}
+
+/// Runs a script over lines of input
+struct Editor {
+    script: Script,
+    quiet: bool,
+    pattern: Vec<u8>,
+    /// whether the line in the pattern space ended in a newline
+    newline: bool,
+    hold: Vec<u8>,
+    line_number: u64,
+    /// the last regex used, which an empty one stands for
+    last_regex: Option<usize>,
+    /// text from `a`, printed at the end of the cycle
+    appended: Vec<Vec<u8>>,
+}
+
+impl Editor {
+    fn new(script: Script, quiet: bool) -> Self {
+        Self {
+            script,
+            quiet,
+            pattern: Vec::new(),
+            newline: true,
+            hold: Vec::new(),
+            line_number: 0,
+            last_regex: None,
+            appended: Vec::new(),
+        }
+    }
+
+    /// Start over for another file, as `-i` edits each one on its own
+    fn reset(&mut self) {
+        self.hold.clear();
+        self.line_number = 0;
+        for command in &mut self.script.commands {
+            command.in_range = false;
+        }
+    }
+
+    /// Run the script over every line of `input`, returning the status `q` quit
+    /// with, if it did
+    fn run(&mut self, input: &mut Input, out: &mut dyn Write) -> Result<Option<i32>> {
+        while let Some(line) = input.next_line()? {
+            self.load(line);
+            let mut pc = 0;
+            let flow = loop {
+                if pc >= self.script.commands.len() {
+                    break Flow::EndCycle { print: true };
+                }
+                if !self.selected(pc, input)? {
+                    pc = match self.script.commands[pc].kind {
+                        Kind::Block(end) => end,
+                        _ => pc + 1,
+                    };
+                    continue;
+                }
+                match self.execute(pc, input, out)? {
+                    Flow::Continue => pc += 1,
+                    flow => break flow,
+                }
+            };
+
+            match flow {
+                Flow::EndCycle { print } => self.end_cycle(print, out)?,
+                Flow::Quit(code) => {
+                    self.end_cycle(true, out)?;
+                    return Ok(Some(code));
+                }
+                Flow::Continue => {}
+            }
+        }
+        Ok(None)
+    }
+
+    fn load(&mut self, line: Line) {
+        self.pattern = line.text;
+        self.newline = line.newline;
+        self.line_number += 1;
+    }
+
+    fn end_cycle(&mut self, print: bool, out: &mut dyn Write) -> Result<()> {
+        if print && !self.quiet {
+            out.write_all(&self.pattern)?;
+            // a last line without a newline is left without one
+            if self.newline {
+                out.write_all(b"\n")?;
+            }
+        }
+        self.flush_appended(out)
+    }
+
+    fn flush_appended(&mut self, out: &mut dyn Write) -> Result<()> {
+        for text in self.appended.drain(..) {
+            write_line(out, &text)?;
+        }
+        Ok(())
+    }
+
+    /// Whether the addresses of the command at `pc` select the current line,
+    /// moving its range along
+    fn selected(&mut self, pc: usize, input: &Input) -> Result<bool> {
+        let command = &self.script.commands[pc];
+        let (first, last, negated, in_range) = (
+            command.first,
+            command.last,
+            command.negated,
+            command.in_range,
+        );
+        let selected = match (first, last) {
+            (None, _) => true,
+            (Some(first), None) => self.matches(first, input)?,
+            (Some(_), Some(last)) if in_range => {
+                let ends = match last {
+                    Address::Line(n) => self.line_number >= n,
+                    address => self.matches(address, input)?,
+                };
+                if ends {
+                    self.script.commands[pc].in_range = false;
+                }
+                true
+            }
+            (Some(first), Some(last)) => {
+                let starts = self.matches(first, input)?;
+                if starts {
+                    // a range whose end is a line already passed is just this line;
+                    // a regex end is only looked for from the next line on
+                    let ends = match last {
+                        Address::Line(n) => n <= self.line_number,
+                        Address::LastLine => input.is_last(),
+                        Address::Regex(_) => false,
+                    };
+                    self.script.commands[pc].in_range = !ends;
+                }
+                starts
+            }
+        };
+        Ok(selected != negated)
+    }
+
+    fn matches(&mut self, address: Address, input: &Input) -> Result<bool> {
+        Ok(match address {
+            Address::Line(n) => self.line_number == n,
+            Address::LastLine => input.is_last(),
+            Address::Regex(regex) => {
+                let regex = self.regex(regex)?;
+                self.script.regexes[regex]
+                    .is_match(&decode(&self.pattern))
+                    .map_err(Error::new)?
+            }
+        })
+    }
+
+    /// The index of `regex`, or of the last one used if it is `None`
+    fn regex(&mut self, regex: Option<usize>) -> Result<usize> {
+        let regex = regex
+            .or(self.last_regex)
+            .ok_or_else(|| Error::new("no previous regular expression"))?;
+        self.last_regex = Some(regex);
+        Ok(regex)
+    }
+
+    fn execute(&mut self, pc: usize, input: &mut Input, out: &mut dyn Write) -> Result<Flow> {
+        let command = &self.script.commands[pc];
+        match &command.kind {
+            Kind::Block(_) | Kind::EndBlock => {}
+            Kind::Substitute(_) => {
+                if self.substitute(pc)? && self.substitution(pc).print {
+                    write_line(out, &self.pattern)?;
+                }
+            }
+            Kind::Transliterate(pairs) => {
+                let transliterated: String = decode(&self.pattern)
+                    .chars()
+                    .map(|c| {
+                        pairs
+                            .iter()
+                            .find(|(from, _)| *from == c)
+                            .map_or(c, |(_, to)| *to)
+                    })
+                    .collect();
+                self.pattern = encode(&transliterated).into_owned();
+            }
+            Kind::Append(text) => self.appended.push(text.clone()),
+            Kind::Insert(text) => write_line(out, text)?,
+            Kind::Change(text) => {
+                // a range is changed as a whole, at its end
+                let in_range = command.last.is_some() && !command.negated && command.in_range;
+                if !in_range {
+                    write_line(out, text)?;
+                }
+                return Ok(Flow::EndCycle { print: false });
+            }
+            Kind::Delete => return Ok(Flow::EndCycle { print: false }),
+            Kind::Print => write_line(out, &self.pattern)?,
+            Kind::LineNumber => writeln!(out, "{}", self.line_number)?,
+            Kind::Next => {
+                if input.is_last() {
+                    return Ok(Flow::EndCycle { print: true });
+                }
+                if !self.quiet {
+                    write_line(out, &self.pattern)?;
+                }
+                self.flush_appended(out)?;
+                if let Some(line) = input.next_line()? {
+                    self.load(line);
+                }
+            }
+            Kind::NextAppend => {
+                // like GNU sed, print the pattern space when there is nothing to append
+                if input.is_last() {
+                    return Ok(Flow::EndCycle { print: true });
+                }
+                self.flush_appended(out)?;
+                if let Some(line) = input.next_line()? {
+                    let pattern = std::mem::take(&mut self.pattern);
+                    self.load(line);
+                    let line = std::mem::replace(&mut self.pattern, pattern);
+                    self.pattern.push(b'\n');
+                    self.pattern.extend(line);
+                }
+            }
+            Kind::Hold => self.hold.clone_from(&self.pattern),
+            Kind::HoldAppend => {
+                self.hold.push(b'\n');
+                self.hold.extend_from_slice(&self.pattern);
+            }
+            Kind::Get => self.pattern.clone_from(&self.hold),
+            Kind::GetAppend => {
+                self.pattern.push(b'\n');
+                self.pattern.extend_from_slice(&self.hold);
+            }
+            Kind::Exchange => std::mem::swap(&mut self.pattern, &mut self.hold),
+            Kind::Quit(code) => return Ok(Flow::Quit(*code)),
+        }
+        Ok(Flow::Continue)
+    }
+
+    fn substitution(&self, pc: usize) -> &Substitute {
+        match &self.script.commands[pc].kind {
+            Kind::Substitute(substitute) => substitute,
+            _ => unreachable!("only called for `s` commands"),
+        }
+    }
+
+    /// Run the `s` command at `pc` on the pattern space, returning whether it
+    /// replaced anything
+    fn substitute(&mut self, pc: usize) -> Result<bool> {
+        let regex = self.regex(self.substitution(pc).regex)?;
+        let substitute = self.substitution(pc);
+        let regex = &self.script.regexes[regex];
+        let text = &*decode(&self.pattern);
+
+        let mut result = String::with_capacity(text.len());
+        let mut copied = 0;
+        let mut pos = 0;
+        let mut count = 0;
+        let mut previous_end = None;
+        let mut replaced = false;
+        while pos <= text.len() {
+            let Some(captures) = regex.captures_from_pos(text, pos).map_err(Error::new)? else {
+                break;
+            };
+            let Some(found) = captures.get(0) else {
+                break;
+            };
+            let empty = found.start() == found.end();
+            // an empty match right after another match doesn't count, as in GNU sed
+            if !(empty && previous_end == Some(found.start())) {
+                count += 1;
+                if count >= substitute.occurrence {
+                    result.push_str(&text[copied..found.start()]);
+                    expand(&substitute.replacement, &captures, &mut result);
+                    copied = found.end();
+                    replaced = true;
+                    if !substitute.global {
+                        break;
+                    }
+                }
+            }
+            previous_end = Some(found.end());
+            pos = if empty {
+                match text[found.end()..].chars().next() {
+                    Some(c) => found.end() + c.len_utf8(),
+                    None => break,
+                }
+            } else {
+                found.end()
+            };
+        }
+        if replaced {
+            result.push_str(&text[copied..]);
+            self.pattern = encode(&result).into_owned();
+        }
+        Ok(replaced)
+    }
+}
+
+fn write_line(out: &mut dyn Write, text: &[u8]) -> io::Result<()> {
+    out.write_all(text)?;
+    out.write_all(b"\n")
+}
+
+/// The chars that stand for bytes 0x80 to 0xff that aren't part of valid UTF-8 in
+/// text from [`decode`], at the end of the last private use plane
+const ESCAPES: u32 = 0x10_FF00;
+
+/// `bytes` as text that the regexes can match. Bytes that aren't valid UTF-8 are
+/// each stood for by a char from [`ESCAPES`], and so are the bytes of chars from
+/// that range, so that [`encode`] gives back exactly the bytes.
+fn decode(bytes: &[u8]) -> Cow<'_, str> {
+    if let Ok(text) = std::str::from_utf8(bytes)
+        && !text.chars().any(|c| c as u32 >= ESCAPES + 0x80)
+    {
+        return Cow::Borrowed(text);
+    }
+    let escape = |byte: &u8| char::from_u32(ESCAPES + u32::from(*byte)).unwrap();
+    let mut text = String::with_capacity(bytes.len());
+    for chunk in bytes.utf8_chunks() {
+        for c in chunk.valid().chars() {
+            if c as u32 >= ESCAPES + 0x80 {
+                text.extend(c.encode_utf8(&mut [0; 4]).as_bytes().iter().map(escape));
+            } else {
+                text.push(c);
+            }
+        }
+        text.extend(chunk.invalid().iter().map(escape));
+    }
+    Cow::Owned(text)
+}
+
+/// The bytes that `text` from [`decode`] stands for
+fn encode(text: &str) -> Cow<'_, [u8]> {
+    if !text.chars().any(|c| c as u32 >= ESCAPES + 0x80) {
+        return Cow::Borrowed(text.as_bytes());
+    }
+    let mut bytes = Vec::with_capacity(text.len());
+    for c in text.chars() {
+        match (c as u32).checked_sub(ESCAPES) {
+            Some(byte @ 0x80..) => bytes.push(byte as u8),
+            _ => bytes.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes()),
+        }
+    }
+    Cow::Owned(bytes)
+}
+
+/// Append `replacement` to `out`, with the groups filled in from `captures`
+fn expand(replacement: &[Replacement], captures: &Captures, out: &mut String) {
+    for part in replacement {
+        match part {
+            Replacement::Text(text) => out.push_str(text),
+            Replacement::Group(group) => {
+                out.push_str(captures.get(*group).map_or("", |found| found.as_str()));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_cmd::{Command, cargo::cargo_bin, prelude::*};
+    use clap::Parser;
+    use std::{
+        env,
+        ffi::OsString,
+        fs,
+        os::unix::{ffi::OsStringExt, fs::symlink},
+    };
+
+    use super::{Sed, SedArgs, create_temp, decode, encode, sed_command};
+    use crate::applet::Applet;
+
+    const LINES: &str = "one\ntwo\nthree\nfour\nfive\n";
+
+    /// Run sed in-process over `input` with the arguments in `args`
+    fn sed(args: &[&str], input: &str) -> (i32, String) {
+        let args =
+            SedArgs::try_parse_from(std::iter::once("sed").chain(args.iter().copied())).unwrap();
+        let (mut out, mut err) = (Vec::new(), Vec::new());
+        let status = sed_command(&args, &mut input.as_bytes(), &mut out, &mut err).unwrap();
+        (status, String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn substitutes() {
+        for (script, expected) in [
+            ("s/o/0/", "0ne\ntw0\nthree\nf0ur\nfive\n"),
+            ("1s/e/E/g", "onE\ntwo\nthree\nfour\nfive\n"),
+            ("3s/e/E/2", "one\ntwo\nthreE\nfour\nfive\n"),
+            (
+                "s/\\(t\\)\\(.\\)/\\2\\1/;s/O/[&]/I",
+                "[o]ne\nwt[o]\nhtree\nf[o]ur\nfive\n",
+            ),
+            (
+                "2!s/x*/-/g",
+                "-o-n-e-\ntwo\n-t-h-r-e-e-\n-f-o-u-r-\n-f-i-v-e-\n",
+            ),
+            (
+                "s|/|\\||;s,.*e$,&\\n,",
+                "one\n\ntwo\nthree\n\nfour\nfive\n\n",
+            ),
+        ] {
+            // Act
+            let (_, out) = sed(&[script], LINES);
+
+            // Assert
+            assert_eq!(out, expected, "{script}");
+        }
+    }
+
+    #[test]
+    fn selects_lines_by_address() {
+        for (args, expected) in [
+            (&["-n", "2,4p"][..], "two\nthree\nfour\n"),
+            (&["-n", "$p"], "five\n"),
+            (&["/two/,/four/d"], "one\nfive\n"),
+            (&["-n", "/^t/,3p"], "two\nthree\n"),
+            (&["-n", "4,2p"], "four\n"),
+            (&["-n", "/E/Ip"], "one\nthree\nfive\n"),
+            (&["2,$!d"], "two\nthree\nfour\nfive\n"),
+            (&["-n", "2{s/t/T/;p;}"], "Two\n"),
+            (&["-e", "/three/=", "-e", "3q5"], "one\ntwo\n3\nthree\n"),
+        ] {
+            // Act
+            let (_, out) = sed(args, LINES);
+
+            // Assert
+            assert_eq!(out, expected, "{args:?}");
+        }
+    }
+
+    #[test]
+    fn edits_text_and_hold_space() {
+        for (script, expected) in [
+            (
+                "2i\\\nbefore\n3a after",
+                "one\nbefore\ntwo\nthree\nafter\nfour\nfive\n",
+            ),
+            ("2,4c\\\nmiddle", "one\nmiddle\nfive\n"),
+            ("y/otf/OTF/", "One\nTwO\nThree\nFOur\nFive\n"),
+            ("n;d", "one\nthree\nfive\n"),
+            ("$!N;s/\\n/-/", "one-two\nthree-four\nfive\n"),
+            ("1!G;h;$!d", "five\nfour\nthree\ntwo\none\n"),
+            ("1h;1d;2H;2x;3,$d", "one\ntwo\n"),
+        ] {
+            // Act
+            let (_, out) = sed(&[script], LINES);
+
+            // Assert
+            assert_eq!(out, expected, "{script}");
+        }
+    }
+
+    #[test]
+    fn quits_with_a_status() {
+        // Act
+        let (status, out) = sed(&["2q3"], LINES);
+
+        // Assert
+        assert_eq!(status, 3);
+        assert_eq!(out, "one\ntwo\n");
+    }
+
+    #[test]
+    fn keeps_a_missing_final_newline() {
+        // Act
+        let (_, out) = sed(&["s/b/c/"], "a\nb");
+
+        // Assert
+        assert_eq!(out, "a\nc");
+    }
+
+    #[test]
+    fn keeps_bytes_that_are_not_utf8() {
+        for bytes in [
+            &b"caf\xe9=1"[..],
+            b"\xf4\x8f\xbf\xbf\xff",
+            b"\xc3",
+            "caf\u{e9}".as_bytes(),
+        ] {
+            // Act
+            let decoded = decode(bytes);
+
+            // Assert
+            assert_eq!(encode(&decoded), bytes, "{bytes:?}");
+        }
+    }
+
+    #[test]
+    fn edits_lines_that_are_not_utf8() {
+        // Arrange
+        let args = SedArgs::try_parse_from([
+            OsString::from("sed"),
+            OsString::from_vec(b"s/\xe9\\(.\\)/\\1\xe8/;s/80/8080/".to_vec()),
+        ])
+        .unwrap();
+        let mut out = Vec::new();
+
+        // Act
+        sed_command(
+            &args,
+            &mut &b"caf\xe9=1\nport=80\n"[..],
+            &mut out,
+            &mut Vec::new(),
+        )
+        .unwrap();
+
+        // Assert
+        assert_eq!(out, b"caf=\xe81\nport=8080\n");
+    }
+
+    #[test]
+    fn rejects_bad_scripts() {
+        for script in ["s/a/b", "k", "2,3}", "{p", "y/ab/c/", "s/a/b/0", "0p"] {
+            // Arrange
+            let args = SedArgs::try_parse_from(["sed", script]).unwrap();
+
+            // Act
+            let result = sed_command(
+                &args,
+                &mut LINES.as_bytes(),
+                &mut Vec::new(),
+                &mut Vec::new(),
+            );
+
+            // Assert
+            assert!(result.is_err(), "{script}");
+        }
+    }
+
+    #[test]
+    fn rewrites_in_place_suffixes() {
+        for (args, expected) in [
+            (&["-i", "s/a/b/"][..], &["--in-place", "s/a/b/"][..]),
+            (&["-i.bak", "p"], &["--in-place=.bak", "p"]),
+            (&["-ni~", "p"], &["-n", "--in-place=~", "p"]),
+            (&["-e", "-i", "-es/i/j/"], &["-e", "-i", "-es/i/j/"]),
+            (&["p", "--", "-i"], &["p", "--", "-i"]),
+        ] {
+            // Arrange
+            let mut argv: Vec<OsString> = std::iter::once("sed")
+                .chain(args.iter().copied())
+                .map(OsString::from)
+                .collect();
+
+            // Act
+            Sed.rewrite_args(&mut argv, 0);
+
+            // Assert
+            assert_eq!(
+                argv[1..],
+                expected.iter().map(OsString::from).collect::<Vec<_>>(),
+                "{args:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn edits_files_in_place() {
+        // Arrange
+        let dir = env::temp_dir().join(format!("rizzybox-sed-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("a.conf"), b"caf\xe9=1\nport=80\n").unwrap();
+        fs::write(dir.join("b.conf"), "port=80\n").unwrap();
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.current_dir(&dir)
+            .args(["sed", "-i.orig", "s/80/8080/", "a.conf", "b.conf"]);
+
+        // Assert
+        cmd.assert().success().stdout("");
+        assert_eq!(
+            fs::read(dir.join("a.conf")).unwrap(),
+            b"caf\xe9=1\nport=8080\n"
+        );
+        assert_eq!(
+            fs::read_to_string(dir.join("b.conf")).unwrap(),
+            "port=8080\n"
+        );
+        assert_eq!(
+            fs::read(dir.join("a.conf.orig")).unwrap(),
+            b"caf\xe9=1\nport=80\n"
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn does_not_follow_links_to_temporary_files() {
+        // Arrange
+        let dir = env::temp_dir().join(format!("rizzybox-sed-temp-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("victim"), "keep\n").unwrap();
+        let planted = dir.join(format!(".a.conf.sed-{}-0", std::process::id()));
+        symlink(dir.join("victim"), &planted).unwrap();
+
+        // Act
+        let (temp, _) = create_temp(&dir.join("a.conf"), "a.conf").unwrap();
+
+        // Assert
+        assert_ne!(temp, planted);
+        assert_eq!(fs::read_to_string(dir.join("victim")).unwrap(), "keep\n");
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn reports_missing_files_and_carries_on() {
+        // Arrange
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.args(["sed", "-n", "$p", "/nonexistent", "-"])
+            .write_stdin(LINES);
+
+        // Assert
+        cmd.assert()
+            .code(2)
+            .stdout("five\n")
+            .stderr("sed: can't read /nonexistent: No such file or directory\n");
+    }
+
+    #[test]
+    fn stops_at_closed_pipe() {
+        // Arrange
+        let mut cmd = std::process::Command::new(cargo_bin(env!("CARGO_PKG_NAME")));
+
+        // Act
+        cmd.args([
+            "sed",
+            "p",
+            concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml"),
+        ])
+        .stdout(crate::closed_pipe());
+
+        // Assert
+        cmd.assert()
+            .code(crate::error::status::BROKEN_PIPE)
+            .stderr("");
+    }
+}
@@ -0,0 +1,553 @@
+use anyhow::Result;
+use clap::{ArgMatches, Command as ClapCommand, CommandFactory, Parser};
+use std::{
+    env::{self, current_exe},
+    ffi::OsString,
+    fs,
+    io::{self, BufWriter, IsTerminal, PipeReader, PipeWriter, Read, Write, stdin},
+    os::unix::{ffi::OsStrExt, process::ExitStatusExt},
+    path::{Path, PathBuf},
+    process::{Child, Command, ExitStatus},
+    thread::{self, JoinHandle},
+};
+
+use super::{Output, Streams, which::which_command};
+use crate::{
+    applet::{self, Applet},
+    config::{self, ShConfig},
+    error::status,
+};
+
+/// An incomplete shell
+#[derive(Parser)]
+pub struct ShArgs {}
+
+pub struct Sh;
+
+impl Applet for Sh {
+    fn name(&self) -> &'static str {
+        "sh"
+    }
+
+    /// `cd` changes the working directory of the process
+    fn in_process(&self) -> bool {
+        false
+    }
+
+    fn command(&self) -> ClapCommand {
+        ShArgs::command()
+    }
+
+    fn run(&self, _matches: &ArgMatches, streams: &mut Streams) -> Result<i32> {
+        sh_command(streams)
+    }
+}
+
+mod builtins {
+    use clap::Parser;
+
+    #[derive(Parser)]
+    /// Change the shell working directory
+    pub struct CdCommand {
+        /// Directory to change to
+        #[clap(default_value = "$HOME")]
+        pub dir: String,
+    }
+
+    #[derive(Parser)]
+    /// Exit the shell
+    pub struct ExitCommand {
+        /// Exit code
+        #[clap(default_value = "0")]
+        pub code: i32,
+    }
+
+    #[derive(Parser)]
+    /// Print the name of the current working directory.
+    pub struct PwdCommand {
+        /// Print the value of $PWD if it names the current working directory
+        #[clap(short = 'L', long, default_value = "true")]
+        pub logical: bool,
+        /// Print the name of the current working directory
+        #[clap(short = 'P', long)]
+        pub physical: bool,
+    }
+}
+
+/// Run commands from stdin until it ends, returning the status of the last one, as
+/// a shell exits with
+pub fn sh_command(streams: &mut Streams) -> Result<i32> {
+    let config = config::get();
+    let mut history = History::load(&config.sh);
+    let mut already_prompted = false;
+    let mut status = 0;
+    loop {
+        // the prompt is only a courtesy, so the shell carries on without it
+        let _ = render_prompt(already_prompted, streams);
+
+        let mut input = String::new();
+        if stdin().read_line(&mut input).unwrap_or(0) == 0 {
+            // end of input, e.g. ctrl-d or the end of a piped script
+            return Ok(status);
+        }
+        let input = input.trim();
+
+        if input.is_empty() {
+            already_prompted = true;
+            continue;
+        }
+
+        history.push(input, &mut streams.stderr)?;
+
+        if input.contains('|') {
+            status = run_pipeline(input, &mut streams.stderr)?;
+            continue;
+        }
+
+        let mut words: Vec<String> = input.split_whitespace().map(str::to_owned).collect();
+        config.expand_alias(&mut words, 0);
+        let parts: Vec<&str> = words.iter().map(String::as_str).collect();
+        let command = parts[0];
+        let args = &parts[1..];
+
+        status = 0;
+        match command {
+            "cd" => cd_command(args, &mut streams.stderr)?,
+            "history" => history.print(&mut streams.stdout)?,
+            "exit" => {
+                streams.stdout.flush()?;
+                return Ok(exit_command(args).unwrap_or(status));
+            }
+            "pwd" => pwd_command(args, streams)?,
+            "(" => writeln!(streams.stderr, "opening paren")?,
+            ")" => writeln!(streams.stderr, "closing paren")?,
+            _ => status = run_command(command, args, &mut streams.stderr)?,
+        }
+    }
+}
+
+/// Run `command` and wait for it, returning its status
+fn run_command(command: &str, args: &[&str], err: &mut dyn Write) -> Result<i32> {
+    match Command::new(command).args(args).spawn() {
+        Ok(mut child) => Ok(exit_code(child.wait()?)),
+        Err(e) => Ok(spawn_failure(command, &e, err)?),
+    }
+}
+
+/// Report that `command` couldn't be run, returning the status a shell gives that
+fn spawn_failure(command: &str, e: &io::Error, err: &mut dyn Write) -> io::Result<i32> {
+    if e.kind() == io::ErrorKind::NotFound {
+        writeln!(err, "{command}: command not found")?;
+        Ok(status::NOT_FOUND)
+    } else {
+        writeln!(err, "{command}: {e}")?;
+        Ok(status::CANNOT_INVOKE)
+    }
+}
+
+/// The status a shell gives a process that exited with `exit`, which is 128 plus
+/// the signal for one that was killed
+fn exit_code(exit: ExitStatus) -> i32 {
+    exit.code()
+        .or_else(|| exit.signal().map(|signal| 128 + signal))
+        .unwrap_or(status::FAILURE)
+}
+
+/// A stage of a pipeline, once it has been started
+enum Stage {
+    Applet(JoinHandle<i32>),
+    Process(Child),
+    /// a command that couldn't be started, with the status for that
+    Failed(i32),
+}
+
+/// Run `cmd1 | cmd2 | ...`. Stages that are rizzybox applets run as threads of
+/// this process instead of spawning a fresh copy of the binary for each of them,
+/// and every stage is connected to the next by an OS pipe. Returns the status of the
+/// last stage, as a shell does.
+fn run_pipeline(input: &str, err: &mut dyn Write) -> Result<i32> {
+    let stages: Vec<Vec<String>> = input
+        .split('|')
+        .map(|stage| {
+            let mut words: Vec<String> = stage.split_whitespace().map(str::to_owned).collect();
+            config::get().expand_alias(&mut words, 0);
+            words
+        })
+        .collect();
+    if stages.iter().any(Vec::is_empty) {
+        writeln!(err, "sh: syntax error near unexpected token `|'")?;
+        return Ok(status::TROUBLE);
+    }
+
+    let mut running = Vec::with_capacity(stages.len());
+    let mut previous_reader = None;
+    for (i, stage) in stages.iter().enumerate() {
+        let (reader, writer) = if i + 1 < stages.len() {
+            let (reader, writer) = io::pipe()?;
+            (Some(reader), Some(writer))
+        } else {
+            (None, None)
+        };
+        let (command, args) = (stage[0].as_str(), &stage[1..]);
+        let stdin = previous_reader.take();
+
+        if let Some(applet) = in_process_applet(command) {
+            let stage = Stage::Applet(spawn_applet(applet, args, stdin, writer));
+            running.push((command, stage));
+        } else {
+            let mut process = Command::new(command);
+            process.args(args);
+            if let Some(stdin) = stdin {
+                process.stdin(stdin);
+            }
+            if let Some(stdout) = writer {
+                process.stdout(stdout);
+            }
+            // `process` is dropped after spawning, closing our copies of the pipe
+            // ends so that the neighbouring stages see EOF
+            let stage = match process.spawn() {
+                Ok(child) => Stage::Process(child),
+                Err(e) => Stage::Failed(spawn_failure(command, &e, err)?),
+            };
+            running.push((command, stage));
+        }
+        previous_reader = reader;
+    }
+
+    let mut status = 0;
+    for (command, stage) in running {
+        status = match stage {
+            Stage::Applet(handle) => match handle.join() {
+                Ok(status) => status,
+                Err(_) => {
+                    writeln!(err, "sh: {command}: the applet panicked")?;
+                    status::FAILURE
+                }
+            },
+            Stage::Process(mut child) => exit_code(child.wait()?),
+            Stage::Failed(status) => status,
+        };
+    }
+    Ok(status)
+}
+
+/// The applet `command` would resolve to if it can run in-process, either through
+/// a link in `PATH` back to rizzybox or because nothing else provides it
+fn in_process_applet(command: &str) -> Option<&'static dyn Applet> {
+    let applet = applet::find(command).filter(|applet| applet.in_process())?;
+    match which_command(false, command, true, &mut io::sink()) {
+        Ok(Some(path))
+            if fs::canonicalize(&path).ok() != current_exe().and_then(fs::canonicalize).ok() =>
+        {
+            None
+        }
+        _ => Some(applet),
+    }
+}
+
+/// Run an applet on its own thread, reading from `stdin` and writing to `stdout`
+/// or inheriting the shell's streams where they aren't given
+fn spawn_applet(
+    applet: &'static dyn Applet,
+    args: &[String],
+    stdin: Option<PipeReader>,
+    stdout: Option<PipeWriter>,
+) -> JoinHandle<i32> {
+    let mut argv: Vec<OsString> = std::iter::once(applet.name())
+        .chain(args.iter().map(String::as_str))
+        .map(OsString::from)
+        .collect();
+    config::get().insert_defaults(&mut argv, 0);
+    applet.rewrite_args(&mut argv, 0);
+
+    let mut streams = Streams {
+        stdin_is_terminal: stdin.is_none() && io::stdin().is_terminal(),
+        stdout_is_terminal: stdout.is_none() && io::stdout().is_terminal(),
+        stdin: match stdin {
+            Some(reader) => Box::new(reader),
+            None => Box::new(io::stdin()) as Box<dyn Read + Send>,
+        },
+        stdout: match stdout {
+            Some(writer) => Box::new(Output(BufWriter::new(writer))),
+            None => Box::new(Output(io::stdout())) as Box<dyn Write + Send>,
+        },
+        stderr: Box::new(io::stderr()),
+    };
+
+    thread::spawn(move || {
+        let result = match applet::command(applet).try_get_matches_from(argv) {
+            Ok(matches) => applet.run(&matches, &mut streams),
+            Err(e) => {
+                let out = if e.use_stderr() {
+                    &mut streams.stderr
+                } else {
+                    &mut streams.stdout
+                };
+                let _ = write!(out, "{e}");
+                let _ = streams.stdout.flush();
+                return if e.use_stderr() {
+                    applet.usage_status()
+                } else {
+                    0
+                };
+            }
+        };
+        // flush before the pipe is closed so the next stage sees all of our output
+        let result = result.and_then(|status| Ok(streams.stdout.flush().map(|()| status)?));
+        applet::exit_status(applet, result, &mut streams.stderr)
+    })
+}
+
+/// The lines entered into the shell, kept in the configured history file if there is one
+struct History {
+    entries: Vec<String>,
+    file: Option<PathBuf>,
+    size: usize,
+}
+
+impl History {
+    fn load(config: &ShConfig) -> Self {
+        let mut history = Self {
+            entries: Vec::new(),
+            file: config.history_file.clone(),
+            size: config.history_size,
+        };
+        if let Some(file) = &config.history_file
+            && let Ok(contents) = fs::read_to_string(file)
+        {
+            history.entries = contents.lines().map(str::to_owned).collect();
+            if history.entries.len() > history.size {
+                history.truncate();
+                // rewrite the file so it doesn't grow past the limit across sessions
+                let entries: String = history
+                    .entries
+                    .iter()
+                    .map(|entry| entry.clone() + "\n")
+                    .collect();
+                let _ = fs::write(file, entries);
+            }
+        }
+        history
+    }
+
+    fn push(&mut self, line: &str, err: &mut dyn Write) -> io::Result<()> {
+        self.entries.push(line.to_owned());
+        self.truncate();
+        if let Some(file) = &self.file {
+            let appended = fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(file)
+                .and_then(|mut file| writeln!(file, "{line}"));
+            if let Err(e) = appended {
+                writeln!(err, "sh: {}: {e}", file.display())?;
+            }
+        }
+        Ok(())
+    }
+
+    fn truncate(&mut self) {
+        let excess = self.entries.len().saturating_sub(self.size);
+        self.entries.drain(..excess);
+    }
+
+    fn print(&self, out: &mut dyn Write) -> io::Result<()> {
+        for (i, entry) in self.entries.iter().enumerate() {
+            writeln!(out, "{:5}  {entry}", i + 1)?;
+        }
+        Ok(())
+    }
+}
+
+fn cd_command(args: &[&str], err: &mut dyn Write) -> io::Result<()> {
+    match parse_command::<builtins::CdCommand>("cd", args) {
+        Ok(cmd) => {
+            let target_dir = match cmd.dir.as_str() {
+                "$HOME" | "~" => env::var_os("HOME").ok_or("HOME not set"),
+                "-" => env::var_os("OLDPWD").ok_or("OLDPWD not set"),
+                "." => Ok(".".into()), // Stay in current directory
+                ".." => Ok("..".into()),
+                _ => Ok(cmd.dir.clone().into()),
+            };
+
+            match target_dir {
+                Ok(dir) => {
+                    // Store current directory as OLDPWD before changing directories
+                    if let Ok(current) = env::current_dir() {
+                        unsafe { env::set_var("OLDPWD", current) };
+                    }
+
+                    if let Err(e) = env::set_current_dir(&dir) {
+                        writeln!(err, "cd: {}: {e}", Path::new(&dir).display())?;
+                    }
+                }
+                Err(msg) => writeln!(err, "cd: {msg}")?,
+            }
+        }
+        Err(e) => writeln!(err, "{e}")?,
+    }
+    Ok(())
+}
+
+/// The status `exit` was asked for, or `None` to exit with that of the last command
+fn exit_command(args: &[&str]) -> Option<i32> {
+    parse_command::<builtins::ExitCommand>("exit", args)
+        .ok()
+        .map(|cmd| cmd.code)
+}
+
+fn pwd_command(args: &[&str], streams: &mut Streams) -> io::Result<()> {
+    match parse_command::<builtins::PwdCommand>("pwd", args) {
+        Ok(cmd) => {
+            let current_dir = env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+            if cmd.physical && !cmd.logical {
+                // Get the physical path
+                let physical_path =
+                    std::fs::canonicalize(current_dir).unwrap_or_else(|_| PathBuf::from("."));
+                print_path(&physical_path, &mut streams.stdout)
+            } else {
+                // Default behavior
+                print_path(&current_dir, &mut streams.stdout)
+            }
+        }
+        Err(e) => writeln!(streams.stderr, "{e}"),
+    }
+}
+
+/// Print `path` byte for byte, whether or not it is valid UTF-8
+fn print_path(path: &Path, out: &mut dyn Write) -> io::Result<()> {
+    out.write_all(path.as_os_str().as_bytes())?;
+    out.write_all(b"\n")
+}
+
+fn render_prompt(already_prompted: bool, streams: &mut Streams) -> io::Result<()> {
+    let prompt = config::get().sh.prompt();
+    if already_prompted {
+        write!(streams.stderr, "\n\r")?;
+    }
+    writeln!(streams.stderr)?;
+    write!(streams.stderr, "{prompt}")?;
+    streams.stderr.flush()?;
+
+    streams.stdout.flush()
+}
+
+fn parse_command<T: Parser>(cmd_name: &str, args: &[&str]) -> Result<T, clap::Error> {
+    T::try_parse_from(std::iter::once(cmd_name).chain(args.iter().copied()))
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_cmd::{Command, cargo::cargo_bin, prelude::*};
+    use predicates::prelude::*;
+
+    #[allow(unused_imports)]
+    use crate::*;
+
+    #[test]
+    fn exits_at_end_of_input() {
+        // Arrange
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.arg("sh");
+        cmd.write_stdin("");
+
+        // Assert
+        cmd.assert().success();
+    }
+
+    #[test]
+    #[cfg(all(feature = "cat", feature = "expand", feature = "yes"))]
+    fn pipeline_of_applets_runs_in_process() {
+        // Arrange
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.arg("sh");
+        cmd.env("PATH", "/nonexistent"); // nothing but rizzybox can provide the applets
+        cmd.write_stdin("yes --amount 3 henlo | cat | expand\n");
+
+        // Assert
+        cmd.assert().success();
+        cmd.assert().stdout("henlo\nhenlo\nhenlo\n\n");
+    }
+
+    #[test]
+    #[cfg(all(feature = "cat", feature = "false", feature = "true"))]
+    fn exits_with_the_status_of_the_last_command() {
+        for (input, status) in [
+            ("false | true\n", 0),
+            ("true | false\n", 1),
+            ("cat /nonexistent | cat\n", 0),
+            ("true | cat /nonexistent\n", 1),
+            ("true | nonexistent-command\n", 127),
+            ("nonexistent-command\n", 127),
+        ] {
+            // Arrange
+            let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+            // Act
+            cmd.arg("sh");
+            cmd.env("PATH", "/nonexistent"); // nothing but rizzybox can provide the applets
+            cmd.write_stdin(input);
+
+            // Assert
+            cmd.assert().code(status);
+        }
+    }
+
+    #[test]
+    fn reports_history_that_cannot_be_written() {
+        // Arrange
+        let script = std::env::temp_dir().join(format!("rizzybox-sh-{}", std::process::id()));
+        std::fs::write(&script, "history\n").unwrap();
+        let mut cmd = std::process::Command::new(cargo_bin(env!("CARGO_PKG_NAME")));
+
+        // Act
+        cmd.arg("sh")
+            .stdin(std::fs::File::open(&script).unwrap())
+            .stdout(std::fs::File::create("/dev/full").unwrap());
+
+        // Assert
+        cmd.assert().code(1).stderr(predicates::str::contains(
+            "sh: write error: No space left on device",
+        ));
+        std::fs::remove_file(script).unwrap();
+    }
+
+    #[test]
+    fn stops_at_closed_pipe() {
+        // Arrange
+        let script = std::env::temp_dir().join(format!("rizzybox-sh-pipe-{}", std::process::id()));
+        std::fs::write(&script, "history\n").unwrap();
+        let mut cmd = std::process::Command::new(cargo_bin(env!("CARGO_PKG_NAME")));
+
+        // Act
+        cmd.arg("sh")
+            .stdin(std::fs::File::open(&script).unwrap())
+            .stdout(closed_pipe());
+
+        // Assert
+        // the prompt goes to stderr, but nothing else should
+        cmd.assert()
+            .code(error::status::BROKEN_PIPE)
+            .stderr(predicates::str::contains("error").not());
+        std::fs::remove_file(script).unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "echo")]
+    fn pipeline_with_external_command() {
+        // Arrange
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.arg("sh");
+        cmd.write_stdin("echo henlo world | /bin/cat\n");
+
+        // Assert
+        cmd.assert().success();
+        cmd.assert().stdout("henlo world\n");
+    }
+}
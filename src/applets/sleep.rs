@@ -0,0 +1,48 @@
+use std::{thread::sleep, time::Duration};
+
+use anyhow::Result;
+use clap::{ArgMatches, Command, CommandFactory, FromArgMatches, Parser};
+
+use super::Streams;
+use crate::{applet::Applet, error::Error};
+
+/// Pause for NUMBER of seconds
+#[derive(Parser)]
+pub struct SleepArgs {
+    /// NUMBER of seconds to sleep
+    pub number: String,
+}
+
+pub struct Sleep;
+
+impl Applet for Sleep {
+    fn name(&self) -> &'static str {
+        "sleep"
+    }
+
+    fn command(&self) -> Command {
+        SleepArgs::command()
+    }
+
+    fn run(&self, matches: &ArgMatches, _streams: &mut Streams) -> Result<i32> {
+        let args = SleepArgs::from_arg_matches(matches)?;
+        sleep_command(&args)?;
+        Ok(0)
+    }
+}
+
+pub fn sleep_command(args: &SleepArgs) -> Result<()> {
+    let sleep_args = args.number.as_str();
+    match sleep_args {
+        "infinity" => loop {
+            sleep(Duration::from_secs(60));
+        },
+        _ => match sleep_args.parse::<u64>() {
+            Ok(s) => {
+                sleep(Duration::from_secs(s));
+                Ok(())
+            }
+            Err(_) => Err(Error::new(format!("invalid time interval '{sleep_args}'")).into()),
+        },
+    }
+}
@@ -1,10 +1,51 @@
-use std::collections::HashSet;
+use std::{
+    collections::HashSet,
+    ffi::{OsStr, OsString},
+    io::Write,
+    os::unix::ffi::OsStrExt,
+};
+
+use anyhow::Result;
+use clap::{ArgMatches, Command, CommandFactory, FromArgMatches, Parser};
+
+use super::Streams;
+use crate::applet::Applet;
 
 const ENG_PREFIXES: [&str; 5] = ["ex", "pre", "post", "re", "un"];
 const ENG_SUFFIXES: [&str; 5] = ["ed", "ing", "er", "est", "ly"];
 
 const WORDLIST: &str = include_str!("../res/mthesaur.csv");
 
+/// Reduce word(s) to their stem(s)
+#[derive(Parser)]
+#[command(disable_help_subcommand = true)] // someone may pass 'help' as a word
+pub struct StemArgs {
+    /// do not output a trailing newline
+    #[arg(long, short, default_value_t = false)]
+    pub nonewline: bool,
+
+    /// words that you would like to stem
+    pub words: Vec<OsString>,
+}
+
+pub struct Stem;
+
+impl Applet for Stem {
+    fn name(&self) -> &'static str {
+        "stem"
+    }
+
+    fn command(&self) -> Command {
+        StemArgs::command()
+    }
+
+    fn run(&self, matches: &ArgMatches, streams: &mut Streams) -> Result<i32> {
+        let args = StemArgs::from_arg_matches(matches)?;
+        stem_command(&args, &mut streams.stdout)?;
+        Ok(0)
+    }
+}
+
 enum EngArticle {
     A,
     An,
@@ -12,11 +53,11 @@ enum EngArticle {
 }
 
 impl EngArticle {
-    fn from_str(s: &str) -> Option<Self> {
-        match s.to_ascii_uppercase() {
-            val if val == *"A" => Some(EngArticle::A),
-            val if val == *"AN" => Some(EngArticle::An),
-            val if val == *"THE" => Some(EngArticle::The),
+    fn from_bytes(s: &[u8]) -> Option<Self> {
+        match s.to_ascii_uppercase().as_slice() {
+            b"A" => Some(EngArticle::A),
+            b"AN" => Some(EngArticle::An),
+            b"THE" => Some(EngArticle::The),
             _ => None,
         }
     }
@@ -79,11 +120,13 @@ impl EngConsonant {
     }
 }
 
+/// A word taken apart, as bytes so that words which aren't valid UTF-8 pass
+/// through unchanged
 #[derive(Debug)]
 struct Word<'a> {
-    prefix: &'a str,
-    stem: &'a str,
-    suffix: &'a str,
+    prefix: &'a [u8],
+    stem: &'a [u8],
+    suffix: &'a [u8],
 }
 
 impl<'a> Word<'a> {
@@ -91,33 +134,36 @@ impl<'a> Word<'a> {
     /// Constructor for a default `Word` with empty fields.
     fn new() -> Word<'a> {
         Self {
-            prefix: "",
-            stem: "",
-            suffix: "",
+            prefix: b"",
+            stem: b"",
+            suffix: b"",
         }
     }
 
     /// Constructs a `Word` object by analyzing the given word.
-    fn from(word: &'a str, wordlist: &'a HashSet<&str>) -> Word<'a> {
+    fn from(word: &'a [u8], wordlist: &'a HashSet<&str>) -> Word<'a> {
         // don't stem articles
-        if EngArticle::from_str(word).is_some() {
+        if EngArticle::from_bytes(word).is_some() {
             return Self {
-                prefix: "",
+                prefix: b"",
                 stem: word,
-                suffix: "",
+                suffix: b"",
             };
         }
 
         let (prefix, without_prefix) = ENG_PREFIXES
             .iter()
-            .find_map(|&prefix| word.strip_prefix(prefix).map(|stem| (prefix, stem)))
-            .unwrap_or(("", word));
+            .find_map(|&prefix| {
+                word.strip_prefix(prefix.as_bytes())
+                    .map(|stem| (prefix.as_bytes(), stem))
+            })
+            .unwrap_or((b"", word));
 
         let (mut stem, suffix) = ENG_SUFFIXES
             .iter()
             .find_map(|&suffix| {
                 without_prefix
-                    .strip_suffix(suffix)
+                    .strip_suffix(suffix.as_bytes())
                     .map(|stem| (stem, suffix))
             })
             .unwrap_or((without_prefix, ""));
@@ -127,10 +173,10 @@ impl<'a> Word<'a> {
         // Example: "biggest" would normally return "bigg" because of how "est"
         // is recognized as a suffix, so we remove the extraneous 'g' to make it
         // "big". This is likely not always correct.
-        if EngConsonant::is_consonant(stem.chars().last().unwrap_or('.')) // '.' is arbitrary
+        if EngConsonant::is_consonant(stem.last().map_or('.', |&byte| char::from(byte))) // '.' is arbitrary
             && (suffix.eq("ing") || suffix.eq("est") || suffix.eq("er") || suffix.eq("ed"))
         {
-            stem = stem.strip_suffix(|_: char| true).unwrap_or(stem);
+            stem = stem.split_last().map_or(stem, |(_, rest)| rest);
         }
 
         // Account for words like "configured", which would normally be reduced
@@ -140,26 +186,29 @@ impl<'a> Word<'a> {
         // meets these conditions.
         let mut potential_stem_matches: HashSet<&str> = HashSet::new();
         for word in wordlist {
-            if word.starts_with(stem) {
+            if word.as_bytes().starts_with(stem) {
                 potential_stem_matches.insert(word);
             }
         }
         stem = potential_stem_matches
             .iter()
             .min_by_key(|s| s.len())
-            .map_or(stem, |v| v);
+            .map_or(stem, |v| v.as_bytes());
 
         Self {
             prefix,
             stem,
-            suffix,
+            suffix: suffix.as_bytes(),
         }
     }
 }
 
 impl std::fmt::Display for Word<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}{}{}", self.prefix, self.stem, self.suffix)
+        for part in [self.prefix, self.stem, self.suffix] {
+            write!(f, "{}", OsStr::from_bytes(part).display())?;
+        }
+        Ok(())
     }
 }
 
@@ -173,37 +222,52 @@ fn read_wordlist() -> HashSet<&'static str> {
     word_set
 }
 
-pub fn stem_command(nonewline: bool, unstemmed_words: &[String]) {
-    let mut to_print = Vec::new();
-    let wordlist = read_wordlist();
+/// Reduces words to their stems, checking them against the word list `stem` ships with
+pub struct Stemmer {
+    wordlist: HashSet<&'static str>,
+}
 
-    for unstemmed_word in unstemmed_words {
-        let word = Word::from(unstemmed_word, &wordlist);
-        to_print.push(word.stem);
+impl Default for Stemmer {
+    fn default() -> Self {
+        Self {
+            wordlist: read_wordlist(),
+        }
     }
+}
 
-    print!(
-        "{}",
-        to_print
-            .into_iter()
-            .collect::<Vec<&str>>()
-            .join(" ")
-            .trim_ascii_end()
-    );
+impl Stemmer {
+    /// The stem of `word`, e.g. `big` for `biggest`. Words are taken as bytes, so ones
+    /// that aren't valid UTF-8 come out unchanged where they aren't stemmed.
+    pub fn stem<'a>(&'a self, word: &'a OsStr) -> &'a OsStr {
+        OsStr::from_bytes(Word::from(word.as_bytes(), &self.wordlist).stem)
+    }
+}
+
+pub fn stem_command(args: &StemArgs, out: &mut impl Write) -> Result<()> {
+    let stemmer = Stemmer::default();
+    let to_print: Vec<&[u8]> = args
+        .words
+        .iter()
+        .map(|word| stemmer.stem(word).as_bytes())
+        .collect();
+
+    out.write_all(to_print.join(&b' ').trim_ascii_end())?;
 
-    if !nonewline {
-        println!();
+    if !args.nonewline {
+        writeln!(out)?;
     }
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     #![expect(non_snake_case)]
 
-    use assert_cmd::Command;
+    use assert_cmd::{Command, cargo::cargo_bin, prelude::*};
+    use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
 
     #[allow(unused_imports)]
-    use rizzybox::*;
+    use crate::*;
 
     #[test]
     fn success() {
@@ -303,4 +367,48 @@ mod tests {
         cmd.assert().success();
         cmd.assert().stdout("big");
     }
+
+    #[test]
+    fn keeps_invalid_utf8_words() {
+        // Arrange
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.arg("stem");
+        cmd.arg(OsStr::from_bytes(b"a\xff.txt"));
+        cmd.arg(OsStr::from_bytes(b"caf\xe9ing"));
+
+        // Assert
+        cmd.assert().success();
+        cmd.assert().stdout(&b"a\xff.txt caf\xe9\n"[..]);
+    }
+
+    #[test]
+    fn stemmer_works_in_process() {
+        // Arrange
+        let stemmer = super::Stemmer::default();
+
+        // Act
+        let stems: Vec<&OsStr> = ["biggest", "the", "henlo"]
+            .into_iter()
+            .map(|word| stemmer.stem(OsStr::new(word)))
+            .collect();
+
+        // Assert
+        assert_eq!(stems, ["big", "the", "henlo"]);
+    }
+
+    #[test]
+    fn stops_at_closed_pipe() {
+        // Arrange
+        let mut cmd = std::process::Command::new(cargo_bin(env!("CARGO_PKG_NAME")));
+
+        // Act
+        cmd.args(["stem", "running"]).stdout(crate::closed_pipe());
+
+        // Assert
+        cmd.assert()
+            .code(crate::error::status::BROKEN_PIPE)
+            .stderr("");
+    }
 }
@@ -0,0 +1,28 @@
+use anyhow::Result;
+use clap::{ArgMatches, Command, CommandFactory, Parser};
+
+use super::Streams;
+use crate::applet::Applet;
+
+/// Do nothing and exit with a success status
+#[derive(Parser)]
+pub struct TrueArgs {}
+
+pub struct True;
+
+impl Applet for True {
+    fn name(&self) -> &'static str {
+        "true"
+    }
+
+    fn command(&self) -> Command {
+        TrueArgs::command()
+    }
+
+    fn run(&self, _matches: &ArgMatches, _streams: &mut Streams) -> Result<i32> {
+        true_command();
+        Ok(0)
+    }
+}
+
+pub fn true_command() {}
@@ -0,0 +1,387 @@
+use std::io::Write;
+
+use anyhow::Result;
+use clap::{ArgMatches, Command, CommandFactory, FromArgMatches, Parser};
+use rustix::system::uname;
+use serde::Serialize;
+
+use super::{Streams, write_json};
+use crate::applet::Applet;
+
+#[derive(Default, Debug, Clone, Copy, clap::ValueEnum)]
+/// Enum for ISA format
+#[expect(non_camel_case_types)] // otherwise, we'd need serde or something similar
+pub enum IsaFormat {
+    /// Whatever the system returns
+    #[default]
+    default,
+    /// ISA strings typically used by Docker
+    docker,
+    /// ISA strings typically used by LLVM
+    llvm,
+    /// ISA strings typically used by Rust
+    rust,
+    /// The most basic ISA strings (e.g., x86, arm)
+    generic,
+}
+
+impl std::fmt::Display for IsaFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+/// Display machine architecture
+#[derive(Parser)]
+pub struct ArchArgs {}
+
+pub struct Arch;
+
+impl Applet for Arch {
+    fn name(&self) -> &'static str {
+        "arch"
+    }
+
+    fn command(&self) -> Command {
+        ArchArgs::command()
+    }
+
+    fn run(&self, _matches: &ArgMatches, streams: &mut Streams) -> Result<i32> {
+        arch_command(&mut streams.stdout)?;
+        Ok(0)
+    }
+}
+
+/// Print system information
+#[derive(Parser)]
+pub struct UnameArgs {
+    /// print all information
+    #[arg(long, short, default_value_t = false)]
+    pub all: bool,
+
+    ///print the kernel name
+    #[arg(long, short = 's')]
+    pub kernel: bool,
+
+    /// print the network node hostname
+    #[arg(long, short = 'n', default_value_t = false)]
+    pub nodename: bool,
+
+    /// print the kernel release
+    #[arg(long = "kernel-release", short = 'r', default_value_t = false)]
+    pub kernel_release: bool,
+
+    /// print the kernel version
+    #[arg(long = "kernel-version", short = 'v', default_value_t = false)]
+    pub kernel_version: bool,
+
+    /// print the machine hardware name
+    #[arg(long, short, default_value_t = false)]
+    pub machine: bool,
+
+    /// print the operating system
+    #[arg(long, short, default_value_t = false)]
+    pub operating_system: bool,
+
+    /// the ISA format to use for CPU info
+    #[arg(long, short, default_value_t)]
+    pub isa_format: IsaFormat,
+
+    /// print all information as JSON, for scripts
+    #[arg(long)]
+    pub json: bool,
+}
+
+pub struct Uname;
+
+impl Applet for Uname {
+    fn name(&self) -> &'static str {
+        "uname"
+    }
+
+    fn command(&self) -> Command {
+        UnameArgs::command()
+    }
+
+    fn run(&self, matches: &ArgMatches, streams: &mut Streams) -> Result<i32> {
+        let args = UnameArgs::from_arg_matches(matches)?;
+        uname_command(&args, &mut streams.stdout)?;
+        Ok(0)
+    }
+}
+
+#[derive(Debug)]
+struct UtsName {
+    sysname: String,
+    nodename: String,
+    release: String,
+    version: String,
+    machine: String,
+}
+
+impl UtsName {
+    /// Create a new `UtsName` struct from the system's uname information
+    fn new() -> Self {
+        let uname = uname();
+        Self {
+            sysname: uname.sysname().to_string_lossy().into_owned(),
+            nodename: uname.nodename().to_string_lossy().into_owned(),
+            release: uname.release().to_string_lossy().into_owned(),
+            version: uname.version().to_string_lossy().into_owned(),
+            machine: uname.machine().to_string_lossy().into_owned(),
+        }
+    }
+
+    /// Get the operating system name based on runtime information
+    fn get_os_string(&self) -> String {
+        match self.sysname.as_str() {
+            "Linux" => {
+                // Check for Android
+                if std::path::Path::new("/system/bin/adb").exists()
+                    || std::path::Path::new("/system/build.prop").exists()
+                {
+                    "Android".to_string()
+                } else {
+                    // Check for common GNU/Linux indicators
+                    if std::path::Path::new("/usr/bin/gnu-gcc").exists()
+                        || std::path::Path::new("/etc/debian_version").exists()
+                        || std::path::Path::new("/etc/redhat-release").exists()
+                    {
+                        "GNU/Linux".to_string()
+                    } else {
+                        "Linux".to_string()
+                    }
+                }
+            }
+            "Darwin" => {
+                if self.machine.starts_with("iPhone") || self.machine.starts_with("iPad") {
+                    "iOS".to_string()
+                } else {
+                    "Darwin".to_string()
+                }
+            }
+            "FreeBSD" => "FreeBSD".to_string(),
+            "DragonFly" => "DragonFly".to_string(),
+            "OpenBSD" => "OpenBSD".to_string(),
+            "SunOS" => "Solaris".to_string(),
+            "Windows" => "💩".to_string(),
+            other => other.to_string(),
+        }
+    }
+
+    /// Everything `uname --all` prints, as `uname --json` prints it
+    fn report(self, isa_format: IsaFormat) -> UnameReport {
+        UnameReport {
+            machine: self.format_machine_arch(isa_format),
+            operating_system: self.get_os_string(),
+            sysname: self.sysname,
+            nodename: self.nodename,
+            release: self.release,
+            version: self.version,
+        }
+    }
+
+    fn format_machine_arch(&self, isa_format: IsaFormat) -> String {
+        format_machine_arch(&self.machine, isa_format)
+    }
+
+    /// Format output according to requested flags
+    fn format_output(&self, args: &UnameArgs) -> String {
+        let isa_format = args.isa_format;
+        if args.all {
+            return format!(
+                "{} {} {} {} {} {}",
+                self.sysname,
+                self.nodename,
+                self.release,
+                self.version,
+                match isa_format {
+                    IsaFormat::default => self.machine.clone(),
+                    _ => self.format_machine_arch(isa_format),
+                },
+                self.get_os_string()
+            );
+        }
+
+        let mut parts = Vec::new();
+
+        let no_args_passed = !args.kernel
+            && !args.nodename
+            && !args.kernel_release
+            && !args.kernel_version
+            && !args.machine
+            && !args.operating_system;
+
+        // Default to kernel name if no flags specified
+        if no_args_passed {
+            parts.push(self.sysname.clone());
+        } else {
+            // Add parts according to flags
+            if args.kernel {
+                parts.push(self.sysname.clone());
+            }
+            if args.nodename {
+                parts.push(self.nodename.clone());
+            }
+            if args.kernel_release {
+                parts.push(self.release.clone());
+            }
+            if args.kernel_version {
+                parts.push(self.version.clone());
+            }
+            if args.machine {
+                match isa_format {
+                    IsaFormat::default => parts.push(self.machine.clone()),
+                    _ => parts.push(self.format_machine_arch(isa_format)),
+                }
+            }
+            if args.operating_system {
+                parts.push(self.get_os_string());
+            }
+        }
+
+        parts.join(" ")
+    }
+}
+
+/// What `uname --json` prints, with `machine` in the requested ISA format
+#[derive(Debug, Serialize)]
+pub struct UnameReport {
+    pub sysname: String,
+    pub nodename: String,
+    pub release: String,
+    pub version: String,
+    pub machine: String,
+    pub operating_system: String,
+}
+
+pub fn arch_command(out: &mut impl Write) -> Result<()> {
+    let utsname = UtsName::new();
+    writeln!(out, "{}", utsname.machine)?;
+    Ok(())
+}
+
+/// `native_arch`, as `uname --machine` or a container platform names it, in the
+/// names of `isa_format`
+pub fn format_machine_arch(native_arch: &str, isa_format: IsaFormat) -> String {
+    match isa_format {
+        IsaFormat::default => native_arch.to_string(),
+
+        IsaFormat::docker => match native_arch {
+            "x86_64" => "amd64".to_string(),
+            "aarch64" => "arm64".to_string(),
+            // docker names 32-bit arm `arm` and puts the `v7` in the platform's variant
+            "armv7l" | "armhf" => "arm".to_string(),
+            "powerpc64le" => "ppc64le".to_string(),
+            "i386" | "i686" => "386".to_string(),
+            _ => native_arch.to_string(),
+        },
+
+        IsaFormat::rust | IsaFormat::llvm => match native_arch {
+            "amd64" => "x86_64".to_string(),
+            "arm64" => "aarch64".to_string(),
+            "arm" | "armhf" => "armv7".to_string(),
+            "ppc64le" => "powerpc64le".to_string(),
+            "386" => "i686".to_string(),
+            _ => native_arch.to_string(),
+        },
+
+        IsaFormat::generic => match native_arch {
+            "x86_64" | "amd64" | "i686" | "i386" => "x86".to_string(),
+            "aarch64" | "arm64" | "armv7l" => "arm".to_string(),
+            "powerpc64le" | "ppc64le" => "ppc".to_string(),
+            _ => native_arch.to_string(),
+        },
+    }
+}
+
+/// Everything `uname --all` prints, with `machine` in `isa_format`
+pub fn uname_report(isa_format: IsaFormat) -> UnameReport {
+    UtsName::new().report(isa_format)
+}
+
+pub fn uname_command(args: &UnameArgs, out: &mut impl Write) -> Result<()> {
+    let utsname = UtsName::new();
+    if args.json {
+        return write_json(&utsname.report(args.isa_format), out);
+    }
+    writeln!(out, "{}", utsname.format_output(args))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_cmd::{Command, cargo::cargo_bin, prelude::*};
+
+    use super::{IsaFormat, format_machine_arch};
+    #[allow(unused_imports)]
+    use crate::*;
+
+    #[test]
+    fn names_architectures_like_docker() {
+        for (machine, expected) in [
+            ("x86_64", "amd64"),
+            ("aarch64", "arm64"),
+            ("armv7l", "arm"),
+            ("i686", "386"),
+            ("riscv64", "riscv64"),
+        ] {
+            // Act
+            let docker = format_machine_arch(machine, IsaFormat::docker);
+
+            // Assert
+            assert_eq!(docker, expected, "{machine}");
+            assert_eq!(format_machine_arch(&docker, IsaFormat::docker), expected);
+        }
+    }
+
+    #[test]
+    fn success() {
+        // Arrange
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.arg("uname");
+
+        // Assert
+        cmd.assert().success();
+    }
+
+    #[test]
+    fn json_has_every_field() {
+        // Arrange
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.args(["uname", "--json", "--isa-format", "docker"]);
+
+        // Assert
+        let output = cmd.assert().success().get_output().stdout.clone();
+        let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+        for field in [
+            "sysname",
+            "nodename",
+            "release",
+            "version",
+            "machine",
+            "operating_system",
+        ] {
+            assert!(json[field].is_string(), "`{field}` is missing");
+        }
+        assert_ne!(json["machine"], "x86_64");
+    }
+
+    #[test]
+    fn stops_at_closed_pipe() {
+        // Arrange
+        let mut cmd = std::process::Command::new(cargo_bin(env!("CARGO_PKG_NAME")));
+
+        // Act
+        cmd.args(["uname", "--all"]).stdout(crate::closed_pipe());
+
+        // Assert
+        cmd.assert()
+            .code(crate::error::status::BROKEN_PIPE)
+            .stderr("");
+    }
+}
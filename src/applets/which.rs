@@ -0,0 +1,166 @@
+use anyhow::Result;
+use clap::{ArgMatches, Command, CommandFactory, FromArgMatches, Parser};
+use serde::Serialize;
+use std::{
+    borrow::Cow,
+    ffi::{OsStr, OsString},
+    io::Write,
+    os::unix::ffi::OsStrExt,
+    path::{Path, PathBuf},
+};
+
+use super::{Streams, write_json};
+use crate::applet::Applet;
+
+/// Write the full path of COMMAND to standard output
+/* TODO: GNU which accepts multiple commands */
+#[derive(Parser)]
+pub struct WhichArgs {
+    /// print all matching pathnames of each argument
+    #[arg(short, default_value_t = false)]
+    pub all_occurrences: bool,
+
+    /// command to search for in PATH
+    pub command: OsString,
+
+    /// silently return 0 if all of the executables were found or 1 otherwise
+    #[arg(short, default_value_t = false)]
+    pub silent: bool,
+
+    /// print the matches as JSON, for scripts
+    #[arg(long)]
+    pub json: bool,
+}
+
+pub struct Which;
+
+impl Applet for Which {
+    fn name(&self) -> &'static str {
+        "which"
+    }
+
+    fn command(&self) -> Command {
+        WhichArgs::command()
+    }
+
+    fn run(&self, matches: &ArgMatches, streams: &mut Streams) -> Result<i32> {
+        let args = WhichArgs::from_arg_matches(matches)?;
+        if args.json {
+            let report = WhichReport {
+                command: args.command.to_string_lossy(),
+                matches: which_matches(args.all_occurrences, &args.command)?
+                    .iter()
+                    .map(|path| path.to_string_lossy().into_owned())
+                    .collect(),
+            };
+            write_json(&report, &mut streams.stdout)?;
+            return Ok(if report.matches.is_empty() { 1 } else { 0 });
+        }
+        let result = which_command(
+            args.all_occurrences,
+            &args.command,
+            args.silent,
+            &mut streams.stdout,
+        )?;
+        Ok(if result.is_some() { 0 } else { 1 })
+    }
+}
+
+pub fn which_command(
+    all_occurrences: bool,
+    command: impl AsRef<OsStr>,
+    silent: bool,
+    out: &mut impl Write,
+) -> Result<Option<PathBuf>> {
+    let matches = which_matches(all_occurrences, command)?;
+    if !silent {
+        for path in &matches {
+            out.write_all(path.as_os_str().as_bytes())?;
+            out.write_all(b"\n")?;
+        }
+    }
+    Ok(matches.into_iter().next())
+}
+
+/// The paths `command` resolves to, in the order of `PATH`; only the first unless
+/// `all_occurrences` is set
+pub fn which_matches(all_occurrences: bool, command: impl AsRef<OsStr>) -> Result<Vec<PathBuf>> {
+    let command_path = Path::new(command.as_ref());
+    if command_path.is_absolute() || command_path.exists() {
+        let full_path = std::fs::canonicalize(command_path)?;
+        return Ok(vec![full_path]);
+    }
+
+    let path = std::env::var_os("PATH").unwrap_or_else(|| "/bin:/usr/bin".into());
+
+    let mut matches = Vec::new();
+    for path in path.as_bytes().split(|&byte| byte == b':') {
+        let full_path = Path::new(OsStr::from_bytes(path)).join(command_path);
+        if full_path.exists() {
+            matches.push(full_path);
+            if !all_occurrences {
+                break;
+            }
+        }
+    }
+    Ok(matches)
+}
+
+/// What `which --json` prints, with U+FFFD in place of bytes that aren't valid UTF-8
+#[derive(Serialize)]
+struct WhichReport<'a> {
+    command: Cow<'a, str>,
+    matches: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_cmd::{Command, cargo::cargo_bin, prelude::*};
+
+    #[allow(unused_imports)]
+    use crate::*;
+
+    #[test]
+    fn success() {
+        // Arrange
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.arg("which");
+
+        // Assert
+        cmd.assert().failure();
+    }
+
+    #[test]
+    fn json_lists_matches() {
+        // Arrange
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.args(["which", "--json", "/bin/sh"]);
+
+        // Assert
+        let output = cmd.assert().success().get_output().stdout.clone();
+        let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(json["command"], "/bin/sh");
+        assert_eq!(json["matches"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn stops_at_closed_pipe() {
+        // Arrange
+        let rizzybox = cargo_bin(env!("CARGO_PKG_NAME"));
+        let mut cmd = std::process::Command::new(&rizzybox);
+
+        // Act
+        cmd.args(["which", env!("CARGO_PKG_NAME")])
+            .env("PATH", rizzybox.parent().unwrap())
+            .stdout(crate::closed_pipe());
+
+        // Assert
+        cmd.assert()
+            .code(crate::error::status::BROKEN_PIPE)
+            .stderr("");
+    }
+}
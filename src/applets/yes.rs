@@ -0,0 +1,147 @@
+use std::{
+    io::{BufWriter, Write},
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+use clap::{ArgMatches, Command, CommandFactory, FromArgMatches, Parser};
+
+use super::Streams;
+use crate::applet::Applet;
+
+/// Repeatedly output lines with TEXT
+#[derive(Parser)]
+#[command(disable_help_subcommand = true)] // in case someone passes 'help' as a word
+pub struct YesArgs {
+    /// AMOUNT of TEXT to output
+    #[arg(long, short, default_value = "0", group = "yes_group")]
+    pub amount: usize,
+
+    /// output TEXT for a DURATION in seconds
+    #[arg(long, short, group = "yes_group")]
+    pub duration: Option<f32>, // Optional because ArgGroup requiring one or the other Arg from the group
+
+    #[arg(default_value = "y")]
+    pub text: String,
+}
+
+pub struct Yes;
+
+impl Applet for Yes {
+    fn name(&self) -> &'static str {
+        "yes"
+    }
+
+    fn command(&self) -> Command {
+        YesArgs::command()
+    }
+
+    fn run(&self, matches: &ArgMatches, streams: &mut Streams) -> Result<i32> {
+        let args = YesArgs::from_arg_matches(matches)?;
+        yes_command(&args, &mut streams.stdout)?;
+        Ok(0)
+    }
+}
+
+pub fn yes_command(args: &YesArgs, out: &mut impl Write) -> Result<()> {
+    let text = &args.text;
+    let mut w = BufWriter::new(out);
+
+    if args.amount > 0 {
+        for _ in 0..args.amount {
+            writeln!(&mut w, "{text}")?;
+        }
+        w.flush()?;
+        return Ok(());
+    }
+
+    let dur = match Duration::try_from_secs_f32(args.duration.unwrap_or_default()) {
+        Ok(d) => d,
+        Err(_) => Duration::ZERO,
+    };
+
+    if dur == Duration::ZERO {
+        loop {
+            writeln!(&mut w, "{text}")?;
+        }
+    }
+
+    let start = Instant::now();
+    while Instant::now().duration_since(start) < dur {
+        writeln!(&mut w, "{text}")?;
+    }
+    w.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_cmd::{Command, cargo::cargo_bin, prelude::*};
+    use std::time::SystemTime;
+
+    #[allow(unused_imports)]
+    use crate::*;
+
+    // Because the `yes` command will run indefinitely by default, tests should always
+    // use either the --amount or --duration args so that the command can exit successfully
+
+    #[test]
+    fn success() {
+        // Arrange
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.arg("yes").arg("--duration").arg("1");
+
+        // Assert
+        cmd.assert().success();
+    }
+
+    #[test]
+    fn amount_outputs_the_specified_amount_of_text() {
+        // Arrange
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.arg("yes");
+        cmd.arg("--amount");
+        cmd.arg("789");
+
+        // Assert
+        cmd.assert().success();
+        cmd.assert()
+            .stdout(predicates::str::contains("y\n".repeat(789)));
+    }
+
+    #[test]
+    fn duration_runs_for_the_specified_duration() {
+        // Arrange
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.arg("yes");
+        cmd.arg("--duration");
+        cmd.arg("1");
+
+        // Assert
+        let start = SystemTime::now();
+        cmd.assert().success();
+        let elapsed = start.elapsed().unwrap();
+        assert!(elapsed.as_secs() >= 1);
+        cmd.assert().stdout(predicates::str::contains("y"));
+    }
+
+    #[test]
+    fn stops_at_closed_pipe() {
+        // Arrange
+        let mut cmd = std::process::Command::new(cargo_bin(env!("CARGO_PKG_NAME")));
+
+        // Act
+        cmd.arg("yes")
+            .args(["--duration", "10"])
+            .stdout(closed_pipe());
+
+        // Assert
+        cmd.assert().code(error::status::BROKEN_PIPE).stderr("");
+    }
+}
@@ -1,16 +1,26 @@
-use std::path::PathBuf;
+use std::{
+    env::current_exe,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
 
 use clap::{
+    ArgGroup, Command, CommandFactory, Parser, Subcommand,
     builder::{
-        styling::{AnsiColor, Effects, Style},
         Styles,
+        styling::{AnsiColor, Effects, Style},
     },
-    ArgAction, Parser, Subcommand,
 };
 use clap_complete::Shell;
-use rizzybox::parse_kv_pair;
+use rizzybox::{
+    applet::{self, APPLETS, Applet},
+    applets::which::which_matches,
+};
 
-use crate::command::uname::IsaFormat;
+use crate::{
+    debug::DebugArgs,
+    install::{InstallMode, InstallShell, is_rizzybox},
+};
 
 // https://github.com/crate-ci/clap-cargo/blob/master/src/style.rs
 const CARGO_STYLING: Styles = Styles::styled()
@@ -48,12 +58,10 @@ const VALID: Style = AnsiColor::Cyan.on_default().effects(Effects::BOLD);
                                  Y8b d88P
                                   "Y88P"
 "#,
-styles = CARGO_STYLING
+styles = CARGO_STYLING,
+group(ArgGroup::new("install_script").args(["install", "install_with_sudo"]).multiple(true))
 )]
 pub struct Cli {
-    #[command(subcommand)]
-    pub command: Option<Commands>,
-
     /// print install script
     #[arg(long)]
     pub install: bool,
@@ -62,103 +70,73 @@ pub struct Cli {
     #[arg(long)]
     pub install_with_sudo: bool,
 
+    /// the shell to print the install script for. detected from $SHELL if omitted
+    #[arg(long, value_enum, requires = "install_script")]
+    pub shell: Option<InstallShell>,
+
     /// create symlinks on the running system. primarily meant to be used for debugging in containers.
     #[arg(long, value_name = "INSTALLATION DIR")]
-    pub install_self: Option<String>,
+    pub install_self: Option<PathBuf>,
 
-    /// list included binaries
-    #[arg(long)]
-    pub list: bool,
-}
+    /// how --install-self puts each applet in place
+    #[arg(long, value_enum, default_value_t, requires = "install_self")]
+    pub mode: InstallMode,
 
-#[derive(Clone, Debug, Subcommand)]
-pub enum PathmungeCommand {
-    /// Insert value at the end of $PATH
-    After {
-        /// The value to add to $PATH
-        path: String,
+    /// with --install-self, skip files that already exist and aren't rizzybox
+    #[arg(long, requires = "install_self")]
+    pub no_clobber: bool,
 
-        /// If value is found in $PATH, move it to the end
-        #[arg(long, short, alias = "move")]
-        force: bool,
-    },
+    /// with --install-self, print what would be done without changing anything
+    #[arg(long, requires = "install_self")]
+    pub dry_run: bool,
 
-    /// Insert value at the beginning of $PATH
-    Before {
-        /// The value to add to $PATH
-        path: String,
+    /// with --install-self, also write man pages for rizzybox and every applet into this dir
+    #[arg(long, value_name = "MAN DIR", requires = "install_self")]
+    pub man_dir: Option<PathBuf>,
 
-        /// If value is found in $PATH, move it to the beginning
-        #[arg(long, short, alias = "move")]
-        force: bool,
-    },
-    Delete {
-        /// The value to delete from $PATH
-        path: String,
-    },
+    /// with --install-self, remove the applets that point back to this rizzybox instead
+    #[arg(
+        long,
+        requires = "install_self",
+        conflicts_with_all = ["mode", "no_clobber", "man_dir"]
+    )]
+    pub uninstall: bool,
+
+    /// list included binaries
+    #[arg(long)]
+    pub list: bool,
+
+    /// print the list as JSON, with each applet's aliases, for scripts
+    #[arg(long, requires = "list")]
+    pub json: bool,
 }
 
 #[derive(Subcommand)]
 pub enum Commands {
-    /// Display machine architecture
-    Arch {},
-
-    /// Print NAME with any leading directory components removed
-    Basename {
-        /// support multiple arguments and treat each as a NAME
-        #[arg(long = "multiple", short = 'a', visible_short_alias = 'm')]
-        multiple: bool,
-
-        /// the NAME of the directory to use
-        name: Vec<String>,
-
-        #[arg(long, short, help = "remove a trailing SUFFIX; implies -a")]
-        suffix: Option<String>,
-
-        /// end each output line with NUL, not newline
-        #[arg(long, short, visible_short_alias = '0')]
-        zero: bool,
-    },
-
-    /// Concatenate and print file contents
-    Cat {
-        /// file to concatenate
-        file: Vec<String>,
-
-        /// language to use for syntax highlighting
-        #[arg(long, short, default_value = "txt")]
-        language: String,
-
-        /// theme to use for colored output
-        #[arg(long, short, default_value = "Dracula")]
-        theme: String,
+    /// Generate completions for your shell
+    Completions {
+        /// the shell to generate completions for
+        shell: Option<Shell>,
 
-        /// show non-printable characters
-        #[arg(long, short = 'A', default_value = "false")]
-        show_all: bool,
+        /// the prefix the applets are installed with, e.g. `rz-`
+        #[arg(long, default_value = "", hide_default_value = true)]
+        prefix: String,
 
-        /// list available themes
+        /// cover every applet, not just the ones that `PATH` leads to this rizzybox for
         #[arg(long)]
-        list_themes: bool,
-
-        /// number all output lines
-        #[arg(long, short)]
-        number_lines: bool,
+        applets: bool,
     },
 
-    /// Clear the terminal screen
-    Clear {},
-
-    /// Generate completions for your shell
-    Completions {
-        /// the shell to generate completions for
-        shell: Option<Shell>,
+    /// Inspect the config file
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
     },
 
     /// Wraps around the Docker CLI to inject Rizzybox into container
     /// images. Allows for interactive debugging with minimal containers
     #[clap(hide = true)]
-    Debug { command: Vec<String> },
+    Debug(DebugArgs),
 
     /// Docker CLI expects plugins to return version metadata
     /// when called with this arg. It is not intended to be
@@ -166,246 +144,183 @@ pub enum Commands {
     #[clap(hide = true)]
     DockerCliPluginMetadata {},
 
-    /// Output each NAME with its last non-slash component and trailing slashes
-    /// removed; if NAME contains no /'s, output '.' (meaning the current directory).
-    #[clap(verbatim_doc_comment)]
-    Dirname {
-        #[arg(required = true)]
-        name: Vec<String>,
-
-        /// end each output line with NUL, not newline
-        #[arg(long, short, visible_short_alias = '0')]
-        zero: bool,
-    },
-
-    /// Write arguments to standard output
-    Echo {
-        /// disable interpretation of backslash escapes
-        #[arg(short = 'E', default_value_t = true)]
-        disable_backslash_escapes: bool,
-
-        /// enable interpretation of backslash escapes
-        #[arg(short = 'e', default_value_t = false)]
-        enable_backslash_escapes: bool,
-
-        /// language to use for syntax highlighting
-        #[arg(long, short, default_value = "txt")]
-        language: String,
-
-        /// do not output a trailing newline
-        #[arg(long, short, default_value_t = false)]
-        nonewline: bool,
-
-        #[arg(default_value = "")]
-        string: Vec<String>,
-
-        /// theme to use for colored output
-        #[arg(long, short, default_value = "Dracula")]
-        theme: String,
+    /// Check an installation for broken links, applets shadowed in PATH and
+    /// whether this binary is statically linked
+    Doctor {
+        /// the installation dir to check; every one in PATH if omitted
+        dir: Option<PathBuf>,
     },
 
-    /// Run a program in a modified environment
-    Env {
-        /// pass ARG as the zeroth argument of COMMAND
-        #[arg(long, short)]
-        argv0: Option<String>,
-
-        /// change working directory to DIR
-        #[arg(long, short)]
-        chdir: Option<String>,
-
-        /// start with an empty environment
-        #[arg(long, short = 'i')]
-        ignore_environment: bool,
-
-        /// end echo output line with NUL, not newline
-        #[arg(long, short = '0', visible_alias = "zero", visible_short_alias = '0')]
-        null: bool,
-
-        /// remove variable from the environment
-        #[arg(action = ArgAction::Append, long, short)]
-        unset: Vec<String>,
-
-        /// KEY=VALUE to set in the environment
-        #[arg(value_parser = parse_kv_pair)]
-        kv_pair: Vec<String>,
-
-        /// command to run in the environment
-        #[arg(last = true)]
-        command: Vec<String>,
-        // FIXME: `last` requires the command to be passed via `--`, which differs from coreutils env
-    },
-
-    ///  Convert tabs in each FILE to spaces, writing to standard output
-    Expand {
-        /// file to concatenate
-        #[arg(default_value = "-")]
-        file: String,
-
-        /// have tabs N characters apart, not 8
-        #[arg(long, short, value_name = "N,LIST", value_delimiter = ',', num_args = 1..)]
-        tabs: Vec<String>,
-    },
-
-    /// Do nothing and exit with a failure status
-    False {},
-
-    /// Create symlinks to files (hard links are not supported yet)
-    Ln {
-        #[arg(long, short)]
-        force: bool,
-
-        #[arg(long, short)]
-        symlink: bool,
-
-        source: String,
-
-        destination: String,
-    },
-
-    /// List information about the FILEs (the current directory by default)
-    Ls {
-        /// do not ignore entries starting with '.'
-        #[arg(long, short)]
-        all: bool,
-
-        /// the PATH to list
-        #[arg(default_value = ".", hide_default_value = true)]
-        path: String,
-    },
-
-    /// Create directories if they do not already exist
-    Mkdir {
-        /// directories to create
-        #[arg(required = true)]
-        dirs: Vec<PathBuf>,
-
-        /// create parent directories as needed
-        #[arg(long, short, env = "RZ_MKDIR_PARENTS")]
-        parents: bool,
-    },
-
-    /// Print the number of cores available to the current process
-    Nproc {
-        /// print the number of cores available to the system
-        #[arg(long, short)]
-        all: bool,
-
-        /// ignore up to N cores
-        #[arg(
-            long,
-            short,
-            value_name = "N",
-            hide_default_value = true,
-            default_value_t = 0
-        )]
-        ignore: usize,
-
-        /// maximum threads to report
-        #[arg(long, env = "OMP_NUM_LIMIT")]
-        omp_num_limit: Option<usize>,
-
-        /// minimum threads to report
-        #[arg(long, env = "OMP_NUM_THREADS")]
-        omp_num_threads: Option<usize>,
-    },
-
-    /// Insert a path into $PATH, only if it isn't already there
-    Pathmunge {
-        #[command(subcommand)]
-        command: PathmungeCommand,
-    },
+    /// Write man pages for rizzybox and every applet into DIR
+    #[clap(hide = true)]
+    Manpages { dir: PathBuf },
 
     /// Wraps around the Docker CLI to inject Rizzybox into container
     /// images. Allows for interactive debugging with minimal containers
     #[clap(hide = true)]
-    Rebug { command: Vec<String> },
-
-    /// An incomplete shell
-    Sh {},
-
-    /// Pause for NUMBER of seconds
-    Sleep {
-        /// NUMBER of seconds to sleep
-        number: String,
-    },
-
-    /// Reduce word(s) to their stem(s)
-    #[clap(disable_help_subcommand = true)] // someone may pass 'help' as a word
-    Stem {
-        /// do not output a trailing newline
-        #[arg(long, short, default_value_t = false)]
-        nonewline: bool,
-
-        /// words that you would like to stem
-        words: Vec<String>,
-    },
-
-    /// Do nothing and exit with a success status
-    True {},
-
-    /// Print system information
-    Uname {
-        /// print all information
-        #[arg(long, short, default_value_t = false)]
-        all: bool,
-
-        ///print the kernel name
-        #[arg(long, short = 's')]
-        kernel: bool,
-
-        /// print the network node hostname
-        #[arg(long, short = 'n', default_value_t = false)]
-        nodename: bool,
-
-        /// print the kernel release
-        #[arg(long = "kernel-release", short = 'r', default_value_t = false)]
-        kernel_release: bool,
-
-        /// print the kernel version
-        #[arg(long = "kernel-version", short = 'v', default_value_t = false)]
-        kernel_version: bool,
-
-        /// print the machine hardware name
-        #[arg(long, short, default_value_t = false)]
-        machine: bool,
-
-        /// print the operating system
-        #[arg(long, short, default_value_t = false)]
-        operating_system: bool,
+    Rebug(DebugArgs),
+}
 
-        /// the ISA format to use for CPU info
-        #[arg(long, short, default_value_t)]
-        isa_format: IsaFormat,
-    },
+#[derive(Subcommand)]
+pub enum ConfigCommand {
+    /// Print the configuration in effect
+    Show,
+}
 
-    /// Write the full path of COMMAND to standard output
-    /* TODO: GNU which accepts multiple commands */
-    Which {
-        /// print all matching pathnames of each argument
-        #[arg(short, default_value_t = false)]
-        all_occurrences: bool,
+/// The full command line of rizzybox: its own flags and subcommands, plus one
+/// subcommand per applet in the registry
+pub fn command() -> Command {
+    build(applet::command)
+}
 
-        /// command to search for in PATH
-        command: String,
+/// [`command`], with the runtime candidates of every applet's arguments filled in
+pub fn completion_command() -> Command {
+    build(|applet| applet.complete(applet::command(applet)))
+}
 
-        /// silently return 0 if all of the executables were found or 1 otherwise
-        #[arg(short, default_value_t = false)]
-        silent: bool,
-    },
+fn build(applet_command: impl Fn(&dyn Applet) -> Command) -> Command {
+    let builtins = Commands::augment_subcommands(Command::new(""));
+    let mut subcommands: Vec<Command> = APPLETS
+        .iter()
+        .map(|applet| applet_command(*applet))
+        .chain(builtins.get_subcommands().cloned())
+        .collect();
+    subcommands.sort_by(|a, b| a.get_name().cmp(b.get_name()));
+
+    // list applets and builtins together, alphabetically
+    Cli::command().subcommands(
+        subcommands
+            .into_iter()
+            .enumerate()
+            .map(|(i, subcommand)| subcommand.display_order(i)),
+    )
+}
 
-    /// Repeatedly output lines with TEXT
-    #[clap(disable_help_subcommand = true)] // in case someone passes 'help' as a word
-    Yes {
-        /// AMOUNT of TEXT to output
-        #[arg(long, short, default_value = "0", group = "yes_group")]
-        amount: usize,
+/// Write completions for rizzybox, and for the applets under the name they are
+/// installed as, i.e. `prefix` followed by their name. Unless `all_applets` is set,
+/// only applets whose name leads to this rizzybox in `PATH` are covered, so that
+/// the completions of the system's own `cat` or `ls` are left alone.
+pub fn write_completions(
+    shell: Shell,
+    prefix: &str,
+    all_applets: bool,
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    // clap_complete panics if it can't write, so render into memory first
+    let mut cmd = completion_command();
+    let name = cmd.get_name().to_string();
+    let mut script = Vec::new();
+    clap_complete::generate(shell, &mut cmd, name, &mut script);
+    out.write_all(&script)?;
+
+    let rizzybox = current_exe()?;
+    for applet in APPLETS {
+        let name = format!("{prefix}{}", applet.name());
+        if !all_applets && !is_installed(&name, &rizzybox) {
+            continue;
+        }
+        let mut cmd = applet.complete(applet::command(*applet));
+        let mut script = Vec::new();
+        clap_complete::generate(shell, &mut cmd, name, &mut script);
+
+        // PowerShell only allows `using` at the top of a script, where rizzybox's has them
+        if shell == Shell::PowerShell {
+            let script = String::from_utf8_lossy(&script);
+            for line in script.lines().filter(|line| !line.starts_with("using ")) {
+                writeln!(out, "{line}")?;
+            }
+        } else {
+            out.write_all(&script)?;
+        }
+    }
+    Ok(())
+}
 
-        /// output TEXT for a DURATION in seconds
-        #[arg(long, short, group = "yes_group")]
-        duration: Option<f32>, // Optional because ArgGroup requiring one or the other Arg from the group
+/// Whether `name` runs `rizzybox` when looked up in `PATH`
+fn is_installed(name: &str, rizzybox: &Path) -> bool {
+    which_matches(false, name)
+        .ok()
+        .and_then(|matches| matches.into_iter().next())
+        .is_some_and(|path| is_rizzybox(&path, rizzybox))
+}
 
-        #[arg(default_value = "y")]
-        text: String,
-    },
+#[cfg(test)]
+mod tests {
+    use assert_cmd::{Command, cargo::cargo_bin, prelude::*};
+
+    #[test]
+    #[cfg(feature = "cat")]
+    fn completions_for_installed_applet_names() {
+        use predicates::prelude::*;
+
+        // Arrange
+        let dir = std::env::temp_dir().join(format!("rizzybox-completions-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir(&dir).unwrap();
+        std::os::unix::fs::symlink(cargo_bin(env!("CARGO_PKG_NAME")), dir.join("rz-which"))
+            .unwrap();
+        let path = format!("{}:{}", dir.display(), std::env::var("PATH").unwrap());
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.args(["completions", "bash", "--prefix", "rz-"])
+            .env("PATH", path);
+
+        // Assert
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains("complete -F _rizzybox"))
+            .stdout(predicates::str::contains(" rz-which\n"))
+            .stdout(predicates::str::contains(" rz-cat\n").not());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn completions_for_every_applet_on_request() {
+        // Arrange
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.args(["completions", "bash", "--prefix", "rz-", "--applets"])
+            .env("PATH", "");
+
+        // Assert
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains(" rz-which\n"));
+    }
+
+    #[test]
+    #[cfg(all(feature = "cat", feature = "highlight", feature = "pathmunge"))]
+    fn completions_offer_runtime_values() {
+        // Arrange
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+        let path = format!("/rizzybox-completions:{}", std::env::var("PATH").unwrap());
+
+        // Act
+        cmd.args(["completions", "zsh", "--applets"])
+            .env("PATH", path);
+
+        // Assert
+        cmd.assert().success();
+        cmd.assert()
+            .stdout(predicates::str::contains("/rizzybox-completions "))
+            .stdout(predicates::str::contains(" Dracula "))
+            .stdout(predicates::str::contains(" Rust "));
+    }
+
+    #[test]
+    fn completions_stop_at_closed_pipe() {
+        // Arrange
+        let mut cmd = std::process::Command::new(cargo_bin(env!("CARGO_PKG_NAME")));
+
+        // Act
+        cmd.args(["completions", "bash"])
+            .stdout(rizzybox::closed_pipe());
+
+        // Assert
+        cmd.assert()
+            .code(rizzybox::error::status::BROKEN_PIPE)
+            .stderr("");
+    }
 }
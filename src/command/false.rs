@@ -1,3 +0,0 @@
-pub fn false_command() {
-    std::process::exit(1);
-}
@@ -1,20 +0,0 @@
-pub mod basename;
-pub mod cat;
-pub mod clear;
-pub mod dirname;
-pub mod echo;
-pub mod env;
-pub mod expand;
-pub mod r#false;
-pub mod ln;
-pub mod ls;
-pub mod mkdir;
-pub mod nproc;
-pub mod pathmunge;
-pub mod sh;
-pub mod sleep;
-pub mod stem;
-pub mod r#true;
-pub mod uname;
-pub mod which;
-pub mod yes;
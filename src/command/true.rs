@@ -1 +0,0 @@
-pub fn true_command() {}
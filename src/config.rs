@@ -0,0 +1,302 @@
+use std::{
+    collections::BTreeMap,
+    env,
+    ffi::OsStr,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use anyhow::{Context, Result, bail};
+use clap::Command;
+use serde::{Deserialize, Serialize};
+
+use crate::applet;
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+/// The user's settings, read from `$XDG_CONFIG_HOME/rizzybox/config.toml`
+/// or the file named by `RIZZYBOX_CONFIG`
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// theme for the applets that highlight their output
+    pub theme: String,
+
+    pub sh: ShConfig,
+
+    pub debug: DebugConfig,
+
+    /// names that run an applet with some arguments up front, e.g. `ll = ["ls", "-a"]`
+    pub aliases: BTreeMap<String, Vec<String>>,
+
+    /// flags given to an applet before the ones on its command line, e.g. `mkdir = ["-p"]`
+    pub defaults: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ShConfig {
+    /// the prompt; `# ` for root, or else `$PS1` or `λ `, if unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompt: Option<String>,
+
+    /// where to keep the lines entered into the shell; no history is kept if unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub history_file: Option<PathBuf>,
+
+    /// how many lines of history to keep
+    pub history_size: usize,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DebugConfig {
+    /// a statically linked rizzybox for `debug` to mount in place of this one, if
+    /// this one is dynamically linked
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub static_binary: Option<PathBuf>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            theme: "Dracula".to_owned(),
+            sh: ShConfig::default(),
+            debug: DebugConfig::default(),
+            aliases: BTreeMap::new(),
+            defaults: BTreeMap::new(),
+        }
+    }
+}
+
+impl Default for ShConfig {
+    fn default() -> Self {
+        Self {
+            prompt: None,
+            history_file: None,
+            history_size: 1000,
+        }
+    }
+}
+
+impl Config {
+    /// Read the config file, falling back to the defaults if there is none
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        let Some(path) = path else {
+            return Ok(Self::default());
+        };
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            // only a file that was asked for explicitly has to exist
+            Err(e)
+                if e.kind() == io::ErrorKind::NotFound
+                    && env::var_os("RIZZYBOX_CONFIG").is_none() =>
+            {
+                return Ok(Self::default());
+            }
+            Err(e) => return Err(e).with_context(|| format!("failed to read {}", path.display())),
+        };
+
+        let mut config: Self = toml::from_str(&contents)
+            .with_context(|| format!("failed to parse {}", path.display()))?;
+        for (alias, args) in &config.aliases {
+            if args.is_empty() {
+                bail!(
+                    "failed to parse {}: alias `{alias}` is empty",
+                    path.display()
+                );
+            }
+            if applet::find(alias).is_some() {
+                bail!(
+                    "failed to parse {}: alias `{alias}` would shadow the applet of the same name; give it flags in [defaults] instead",
+                    path.display()
+                );
+            }
+        }
+        expand_home(&mut config.sh.history_file);
+        expand_home(&mut config.debug.static_binary);
+        Ok(config)
+    }
+
+    /// Expand an alias at `args[at]`, then insert the configured defaults of the
+    /// applet that ends up there after its name
+    pub fn expand_args<S: AsRef<OsStr> + From<String>>(&self, args: &mut Vec<S>, at: usize) {
+        self.expand_alias(args, at);
+        self.insert_defaults(args, at);
+    }
+
+    /// Replace an alias at `args[at]` with what it stands for
+    pub fn expand_alias<S: AsRef<OsStr> + From<String>>(&self, args: &mut Vec<S>, at: usize) {
+        if let Some(expansion) = Self::name_at(args, at).and_then(|name| self.aliases.get(name)) {
+            args.splice(at..=at, expansion.iter().cloned().map(S::from));
+        }
+    }
+
+    /// Insert the configured defaults of the applet at `args[at]` after its name
+    pub fn insert_defaults<S: AsRef<OsStr> + From<String>>(&self, args: &mut Vec<S>, at: usize) {
+        if let Some(defaults) = Self::name_at(args, at).and_then(|name| self.defaults.get(name)) {
+            args.splice(at + 1..at + 1, defaults.iter().cloned().map(S::from));
+        }
+    }
+
+    /// The applet or alias name at `args[at]`; names are always valid UTF-8
+    fn name_at<S: AsRef<OsStr>>(args: &[S], at: usize) -> Option<&str> {
+        args.get(at).and_then(|name| name.as_ref().to_str())
+    }
+
+    /// Use the configured defaults in place of the ones `command` was defined with
+    pub fn apply(&self, command: Command) -> Command {
+        if command.get_arguments().any(|arg| arg.get_id() == "theme") {
+            command.mut_arg("theme", |arg| arg.default_value(self.theme.clone()))
+        } else {
+            command
+        }
+    }
+
+    /// The configuration as it is in effect, in the format of the config file
+    pub fn show(&self) -> Result<String> {
+        let mut effective = self.clone();
+        effective.sh.prompt = Some(self.sh.prompt());
+        Ok(toml::to_string_pretty(&effective)?)
+    }
+}
+
+impl ShConfig {
+    /// The prompt in effect
+    pub fn prompt(&self) -> String {
+        if let Some(prompt) = &self.prompt {
+            return prompt.clone();
+        }
+        if rustix::process::geteuid().is_root() {
+            return "# ".to_owned();
+        }
+        env::var("PS1").unwrap_or("λ ".to_owned())
+    }
+}
+
+/// Replace a leading `~` in `path` with `$HOME`
+fn expand_home(path: &mut Option<PathBuf>) {
+    if let Some(inner) = path
+        && let Ok(rest) = inner.strip_prefix("~")
+        && let Some(home) = env::var_os("HOME")
+    {
+        *path = Some(Path::new(&home).join(rest));
+    }
+}
+
+/// Where the config file is looked for
+pub fn path() -> Option<PathBuf> {
+    if let Some(path) = env::var_os("RIZZYBOX_CONFIG") {
+        return Some(PathBuf::from(path));
+    }
+    let config_home = env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+    Some(config_home.join("rizzybox").join("config.toml"))
+}
+
+/// Load the config file for the rest of the process to use through [`get`]
+pub fn init() -> Result<&'static Config> {
+    let config = Config::load(path().as_deref())?;
+    Ok(CONFIG.get_or_init(|| config))
+}
+
+/// The config loaded by [`init`], or the defaults if it hasn't been
+pub fn get() -> &'static Config {
+    CONFIG.get_or_init(Config::default)
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_cmd::Command;
+    use std::{fs, path::PathBuf};
+
+    /// Write `contents` to a config file of its own for one test
+    fn config_file(test: &str, contents: &str) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("rizzybox-{test}-{}.toml", std::process::id()));
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn show_prints_effective_config() {
+        // Arrange
+        let path = config_file("config-show", "[sh]\nprompt = \"rz> \"\n");
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.args(["config", "show"]).env("RIZZYBOX_CONFIG", &path);
+
+        // Assert
+        cmd.assert().success();
+        cmd.assert()
+            .stdout(predicates::str::contains(format!("# {}\n", path.display())))
+            .stdout(predicates::str::contains("theme = \"Dracula\"\n"))
+            .stdout(predicates::str::contains("prompt = \"rz> \"\n"))
+            .stdout(predicates::str::contains("history_size = 1000\n"));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn parse_errors_name_the_file() {
+        // Arrange
+        let path = config_file("config-error", "theme = 3\n");
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.args(["config", "show"]).env("RIZZYBOX_CONFIG", &path);
+
+        // Assert
+        cmd.assert().failure();
+        cmd.assert()
+            .stderr(predicates::str::contains(format!(
+                "failed to parse {}",
+                path.display()
+            )))
+            .stderr(predicates::str::contains("line 1, column 9"));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "echo")]
+    fn applets_run_despite_parse_errors() {
+        // Arrange
+        let path = config_file("config-fallback", "theme = 3\n");
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.args(["echo", "hi"]).env("RIZZYBOX_CONFIG", &path);
+
+        // Assert
+        cmd.assert()
+            .success()
+            .stdout("hi\n")
+            .stderr(predicates::str::contains(format!(
+                "rizzybox: warning: using the default configuration: failed to parse {}",
+                path.display()
+            )));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "echo")]
+    fn aliases_and_defaults_apply() {
+        // Arrange
+        let path = config_file(
+            "config-aliases",
+            "[aliases]\nsay = [\"echo\", \"hello\"]\n\n[defaults]\necho = [\"-n\"]\n",
+        );
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.args(["say", "world"]).env("RIZZYBOX_CONFIG", &path);
+
+        // Assert
+        cmd.assert().success();
+        cmd.assert().stdout("hello world");
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,687 @@
+use std::{
+    ffi::{OsStr, OsString},
+    io::{Write, sink},
+    iter,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use anyhow::{Context, Result, bail};
+use clap::Args;
+#[cfg(feature = "uname")]
+use rizzybox::applets::uname::{IsaFormat, format_machine_arch, uname_report};
+use rizzybox::{applets::which::which_command, config, error::status};
+
+use crate::elf::Elf;
+
+/// Where `debug` installs the applets inside the container before dropping into `sh`
+const INSTALL_DIR: &str = "/tmp/rbin";
+
+#[derive(Args)]
+pub struct DebugArgs {
+    /// the container engine to run, by name or path; docker, podman and nerdctl are
+    /// known, anything else is assumed to take docker's arguments. The first of those
+    /// found in PATH if not given
+    #[arg(long, value_name = "ENGINE", env = "RZ_CONTAINER_ENGINE")]
+    pub engine: Option<OsString>,
+
+    /// an extra argument for `<engine> run`, e.g. `--engine-arg=--platform=linux/arm64`;
+    /// may be repeated
+    #[arg(long, value_name = "ARG", allow_hyphen_values = true)]
+    pub engine_arg: Vec<OsString>,
+
+    /// print the engine command instead of running it
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// mount this rizzybox even if it is dynamically linked; it only runs in images
+    /// that have its loader and libraries
+    #[arg(long)]
+    pub allow_dynamic: bool,
+
+    /// a running container to attach to instead of starting IMAGE; the helper
+    /// container shares its pid, network and ipc namespaces and its volumes
+    #[arg(long, value_name = "CONTAINER")]
+    pub target: Option<String>,
+
+    /// the IMAGE to debug (unless --target is given), then the arguments for
+    /// rizzybox inside of it; an interactive shell if there are none
+    #[arg(trailing_var_arg = true)]
+    pub command: Vec<OsString>,
+}
+
+/// The container engines whose arguments differ from docker's
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum EngineKind {
+    Docker,
+    Podman,
+    Nerdctl,
+}
+
+/// The container engine that `debug` runs
+struct Engine {
+    program: OsString,
+    kind: EngineKind,
+    /// podman running as an unprivileged user, which needs the user namespace set up
+    rootless: bool,
+}
+
+impl Engine {
+    /// The engine named by `choice`, or the first one found in `PATH`
+    fn detect(choice: Option<&OsStr>) -> Result<Self> {
+        let program = match choice {
+            Some(program) => program.to_owned(),
+            None => first_engine_in_path()?.into(),
+        };
+        let kind = match Path::new(&program).file_name().and_then(OsStr::to_str) {
+            Some("podman") => EngineKind::Podman,
+            Some("nerdctl") => EngineKind::Nerdctl,
+            _ => EngineKind::Docker,
+        };
+        let rootless = kind == EngineKind::Podman && is_rootless_podman(&program);
+        Ok(Self {
+            program,
+            kind,
+            rootless,
+        })
+    }
+
+    /// The architecture of `image`, if it has been pulled
+    #[cfg(feature = "uname")]
+    fn image_arch(&self, image: &OsStr) -> Option<String> {
+        let output = Command::new(&self.program)
+            .args(["image", "inspect", "--format", "{{.Architecture}}"])
+            .arg(image)
+            .output()
+            .ok()
+            .filter(|output| output.status.success())?;
+        let arch = String::from_utf8_lossy(&output.stdout).trim().to_owned();
+        (!arch.is_empty()).then_some(arch)
+    }
+
+    /// The image that the running `container` was started from
+    fn container_image(&self, container: &str) -> Result<String> {
+        let output = Command::new(&self.program)
+            .args(["inspect", "--format", "{{.Image}}", container])
+            .output()
+            .with_context(|| format!("failed to run {}", self.program.display()))?;
+        if !output.status.success() {
+            bail!(
+                "cannot attach to '{container}': {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+    }
+}
+
+/// A rizzybox for the architecture of `image` from the cache, when it isn't the one
+/// we run on. The architecture comes from a `--platform` among the engine args, or
+/// else from the image itself; an image that hasn't been pulled yet is assumed to
+/// match us, as that is what the engine will pull.
+#[cfg(feature = "uname")]
+fn binary_for_platform(
+    args: &DebugArgs,
+    engine: &Engine,
+    image: Option<&OsStr>,
+) -> Result<Option<PathBuf>> {
+    let Some(image_arch) = platform_arch(&args.engine_arg)
+        .or_else(|| image.and_then(|image| engine.image_arch(image)))
+    else {
+        return Ok(None);
+    };
+    let image_arch = format_machine_arch(&image_arch, IsaFormat::docker);
+    let our_arch = uname_report(IsaFormat::docker).machine;
+    if image_arch == our_arch {
+        return Ok(None);
+    }
+
+    let binary = cache_dir()
+        .context("cannot find the cache dir; HOME is not set")?
+        .join(&image_arch)
+        .join("rizzybox");
+    if !binary.is_file() {
+        bail!(
+            "the image is for {image_arch}, but this rizzybox is for {our_arch} and there is no {}. \
+             Build a static rizzybox for {image_arch} (e.g. with `cargo build --target {}-unknown-linux-musl`) and put it there",
+            binary.display(),
+            format_machine_arch(&image_arch, IsaFormat::rust)
+        );
+    }
+    if !args.allow_dynamic
+        && let Some(dependencies) = Elf::read(&binary)?.dependencies()?
+    {
+        bail!(
+            "{} is dynamically linked against {dependencies}; replace it with a static build, or pass --allow-dynamic",
+            binary.display()
+        );
+    }
+    Ok(Some(binary))
+}
+
+/// Without `uname`, rizzybox can't tell which architecture it runs on, so it always
+/// mounts itself
+#[cfg(not(feature = "uname"))]
+fn binary_for_platform(
+    _args: &DebugArgs,
+    _engine: &Engine,
+    _image: Option<&OsStr>,
+) -> Result<Option<PathBuf>> {
+    Ok(None)
+}
+
+/// The architecture of a `--platform` in `engine_args`, e.g. `arm64` for `linux/arm64/v8`
+#[cfg(feature = "uname")]
+fn platform_arch(engine_args: &[OsString]) -> Option<String> {
+    let mut engine_args = engine_args.iter().map(|arg| arg.to_string_lossy());
+    while let Some(arg) = engine_args.next() {
+        let platform = match arg.strip_prefix("--platform") {
+            Some("") => engine_args.next()?.into_owned(),
+            Some(rest) if rest.starts_with('=') => rest[1..].to_owned(),
+            _ => continue,
+        };
+        return platform.split('/').nth(1).map(str::to_owned);
+    }
+    None
+}
+
+/// Where rizzybox builds for other architectures are kept, as `ARCH/rizzybox`:
+/// `$XDG_CACHE_HOME/rizzybox`, or `~/.cache/rizzybox`
+#[cfg(feature = "uname")]
+fn cache_dir() -> Option<PathBuf> {
+    let cache_home = std::env::var_os("XDG_CACHE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".cache")))?;
+    Some(cache_home.join("rizzybox"))
+}
+
+/// The rizzybox to mount into the container: `rizzybox` if it is statically linked,
+/// or else the static build configured as `static_binary` under `[debug]`. Scratch
+/// and distroless images lack the loader a dynamically linked one needs, and it would
+/// fail with a puzzling "no such file or directory", so that takes `allow_dynamic`.
+fn binary_to_mount(rizzybox: &Path, allow_dynamic: bool, err: &mut dyn Write) -> Result<PathBuf> {
+    let Some(dependencies) = Elf::read(rizzybox)?.dependencies()? else {
+        return Ok(rizzybox.to_owned());
+    };
+    if let Some(static_binary) = &config::get().debug.static_binary {
+        if let Some(its_dependencies) = Elf::read(static_binary)?.dependencies()? {
+            bail!(
+                "{} is configured as the static_binary for debug, but is dynamically linked against {its_dependencies}",
+                static_binary.display()
+            );
+        }
+        return Ok(static_binary.clone());
+    }
+    if allow_dynamic {
+        writeln!(
+            err,
+            "rizzybox: warning: {} is dynamically linked against {dependencies}; it only runs in images that have them",
+            rizzybox.display()
+        )?;
+        return Ok(rizzybox.to_owned());
+    }
+    bail!(
+        "{} is dynamically linked against {dependencies}, which scratch and distroless images lack. \
+         Build a static rizzybox (e.g. for the x86_64-unknown-linux-musl target) and set it as \
+         static_binary under [debug] in the config file, or pass --allow-dynamic if the image has them",
+        rizzybox.display()
+    )
+}
+
+/// The first known container engine found in `PATH`
+fn first_engine_in_path() -> Result<&'static str> {
+    for engine in ["docker", "podman", "nerdctl"] {
+        if which_command(false, engine, true, &mut sink())?.is_some() {
+            return Ok(engine);
+        }
+    }
+    bail!(
+        "No container engine was detected. Please ensure that one of (docker, podman, nerdctl) are available in PATH, or pass --engine"
+    )
+}
+
+/// Whether podman at `program` runs without root; assumed not if it can't tell
+fn is_rootless_podman(program: &OsStr) -> bool {
+    Command::new(program)
+        .args(["info", "--format", "{{.Host.Security.Rootless}}"])
+        .output()
+        .is_ok_and(|output| output.status.success() && output.stdout.trim_ascii() == b"true")
+}
+
+/// Run rizzybox in a container with the binary at `rizzybox` mounted as its
+/// entrypoint, returning the exit status of the container. With `--dry-run`, the
+/// engine command is written to `out` instead.
+pub fn debug(
+    rizzybox: &Path,
+    args: &DebugArgs,
+    out: &mut dyn Write,
+    err: &mut dyn Write,
+) -> Result<i32> {
+    let engine = Engine::detect(args.engine.as_deref())?;
+
+    let image = match &args.target {
+        Some(target) => {
+            writeln!(
+                err,
+                "rizzybox: attaching to {target}; its filesystem is at /proc/1/root"
+            )?;
+            Some(OsString::from(engine.container_image(target)?))
+        }
+        None => None,
+    };
+    let image_name = image
+        .as_deref()
+        .or(args.command.first().map(OsString::as_os_str));
+    let rizzybox = match binary_for_platform(args, &engine, image_name)? {
+        Some(binary) => binary,
+        None => binary_to_mount(rizzybox, args.allow_dynamic, err)?,
+    };
+    let run_args = run_args(&rizzybox, args, &engine, image)?;
+
+    if args.dry_run {
+        let words: Vec<String> = iter::once(&engine.program)
+            .chain(&run_args)
+            .map(|word| quote(&word.to_string_lossy()))
+            .collect();
+        writeln!(out, "{}", words.join(" "))?;
+        return Ok(0);
+    }
+
+    let status = Command::new(&engine.program)
+        .args(run_args)
+        .status()
+        .with_context(|| format!("failed to run {}", engine.program.display()))?;
+    Ok(status.code().unwrap_or(status::FAILURE))
+}
+
+/// The arguments of `<engine> run` for `args`. When attaching to a target, `image`
+/// is the one it runs, and all of `args.command` is for rizzybox.
+fn run_args(
+    rizzybox: &Path,
+    args: &DebugArgs,
+    engine: &Engine,
+    image: Option<OsString>,
+) -> Result<Vec<OsString>> {
+    let mut run_args: Vec<OsString> = ["run", "--rm", "-it"].map(OsString::from).to_vec();
+    match (&args.target, engine.rootless) {
+        // keep our own uid, so that the mounted binary and any files written to
+        // volumes stay ours
+        (None, true) => run_args.push("--userns=keep-id".into()),
+        (Some(target), true) => {
+            run_args.push(format!("--userns=container:{target}").into());
+            run_args.extend(["--user", "0:0"].map(OsString::from));
+        }
+        // we need to be root to ensure symlinks can be created in /bin
+        (_, false) => run_args.extend(["--user", "0:0"].map(OsString::from)),
+    }
+
+    if let Some(target) = &args.target {
+        for namespace in ["--pid", "--network", "--ipc"] {
+            run_args.push(format!("{namespace}=container:{target}").into());
+        }
+        // nerdctl has no --volumes-from
+        if engine.kind != EngineKind::Nerdctl {
+            run_args.push(format!("--volumes-from={target}").into());
+        }
+    }
+
+    let mut volume = rizzybox.as_os_str().to_owned();
+    volume.push(":/bin/rizzybox");
+    run_args.extend(["-v".into(), volume, "--entrypoint=/bin/rizzybox".into()]);
+    run_args.extend(args.engine_arg.iter().cloned());
+
+    let (image, rizzybox_args) = match image {
+        Some(image) => (image, &args.command[..]),
+        None => match args.command.split_first() {
+            Some((image, rizzybox_args)) => (image.clone(), rizzybox_args),
+            None => bail!("an IMAGE or --target is required"),
+        },
+    };
+    run_args.push(image);
+    if rizzybox_args.is_empty() {
+        // invoke --install-self and drop into an interactive shell
+        run_args.extend(["--install-self", INSTALL_DIR].map(OsString::from));
+    } else {
+        run_args.extend(rizzybox_args.iter().cloned());
+    }
+    Ok(run_args)
+}
+
+/// `word` as `sh` would need it to be typed, quoted only if necessary
+fn quote(word: &str) -> String {
+    let plain = |c: char| c.is_ascii_alphanumeric() || "-_./:=,@%+".contains(c);
+    if !word.is_empty() && word.chars().all(plain) {
+        word.to_owned()
+    } else {
+        format!("'{}'", word.replace('\'', r"'\''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_cmd::Command;
+    use std::{
+        env,
+        ffi::OsString,
+        fs,
+        os::unix::fs::PermissionsExt,
+        path::{Path, PathBuf},
+    };
+
+    #[cfg(feature = "uname")]
+    use super::platform_arch;
+    use super::{DebugArgs, Engine, EngineKind, run_args};
+    use crate::elf::Elf;
+
+    fn joined(args: &[OsString]) -> String {
+        args.iter()
+            .map(|arg| arg.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn debug_args(target: Option<&str>, command: &[&str]) -> DebugArgs {
+        DebugArgs {
+            engine: None,
+            engine_arg: Vec::new(),
+            dry_run: false,
+            allow_dynamic: false,
+            target: target.map(str::to_owned),
+            command: command.iter().map(OsString::from).collect(),
+        }
+    }
+
+    fn engine(kind: EngineKind, rootless: bool) -> Engine {
+        Engine {
+            program: "engine".into(),
+            kind,
+            rootless,
+        }
+    }
+
+    /// A dir holding an executable script called `name`, which answers `info`,
+    /// `inspect` and `image inspect` like podman does, the last with `$FAKE_ARCH` if
+    /// set, and prints any other arguments it gets
+    fn fake_engine(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("rizzybox-engine-{name}-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let script = dir.join(name);
+        fs::write(
+            &script,
+            "#!/bin/sh\n\
+             case $1 in\n\
+             info) echo true ;;\n\
+             inspect) echo sha256:abc ;;\n\
+             image) [ -n \"$FAKE_ARCH\" ] && echo \"$FAKE_ARCH\" ;;\n\
+             *) echo \"$@\"; exit 3 ;;\n\
+             esac\n",
+        )
+        .unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+        dir
+    }
+
+    /// Write an ELF header without any program headers, which is as static as it gets
+    fn write_static_binary(path: &Path) {
+        let mut data = b"\x7fELF\x02\x01".to_vec();
+        data.resize(64, 0);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, data).unwrap();
+    }
+
+    #[test]
+    fn runs_an_image_with_a_shell() {
+        // Arrange
+        let args = debug_args(None, &["alpine"]);
+
+        // Act
+        let run_args = run_args(
+            Path::new("/usr/bin/rizzybox"),
+            &args,
+            &engine(EngineKind::Docker, false),
+            None,
+        )
+        .unwrap();
+
+        // Assert
+        assert_eq!(
+            joined(&run_args),
+            "run --rm -it --user 0:0 -v /usr/bin/rizzybox:/bin/rizzybox \
+             --entrypoint=/bin/rizzybox alpine --install-self /tmp/rbin"
+        );
+    }
+
+    #[test]
+    fn attaches_to_a_target() {
+        // Arrange
+        let args = debug_args(Some("web"), &["ls", "/proc/1/root"]);
+
+        // Act
+        let run_args = run_args(
+            Path::new("/rizzybox"),
+            &args,
+            &engine(EngineKind::Docker, false),
+            Some("sha256:abc".into()),
+        )
+        .unwrap();
+
+        // Assert
+        assert_eq!(
+            joined(&run_args),
+            "run --rm -it --user 0:0 --pid=container:web --network=container:web \
+             --ipc=container:web --volumes-from=web -v /rizzybox:/bin/rizzybox \
+             --entrypoint=/bin/rizzybox sha256:abc ls /proc/1/root"
+        );
+    }
+
+    #[test]
+    fn engine_specific_arguments() {
+        for (kind, rootless, target, expected) in [
+            (
+                EngineKind::Podman,
+                true,
+                None,
+                "run --rm -it --userns=keep-id -v /rizzybox:/bin/rizzybox \
+                 --entrypoint=/bin/rizzybox --platform=linux/arm64 alpine sh",
+            ),
+            (
+                EngineKind::Podman,
+                true,
+                Some("web"),
+                "run --rm -it --userns=container:web --user 0:0 --pid=container:web \
+                 --network=container:web --ipc=container:web --volumes-from=web \
+                 -v /rizzybox:/bin/rizzybox --entrypoint=/bin/rizzybox \
+                 --platform=linux/arm64 alpine sh",
+            ),
+            (
+                EngineKind::Nerdctl,
+                false,
+                Some("web"),
+                "run --rm -it --user 0:0 --pid=container:web --network=container:web \
+                 --ipc=container:web -v /rizzybox:/bin/rizzybox --entrypoint=/bin/rizzybox \
+                 --platform=linux/arm64 alpine sh",
+            ),
+        ] {
+            // Arrange
+            let mut args = debug_args(target, &["sh"]);
+            args.engine_arg.push("--platform=linux/arm64".into());
+            if target.is_none() {
+                args.command.insert(0, "alpine".into());
+            }
+
+            // Act
+            let run_args = run_args(
+                Path::new("/rizzybox"),
+                &args,
+                &engine(kind, rootless),
+                target.map(|_| "alpine".into()),
+            )
+            .unwrap();
+
+            // Assert
+            assert_eq!(joined(&run_args), expected, "{kind:?}");
+        }
+    }
+
+    #[test]
+    fn dry_run_prints_the_engine_command() {
+        // Arrange
+        let dir = fake_engine("podman");
+        write_static_binary(&dir.join("rizzybox-static"));
+        fs::write(
+            dir.join("config.toml"),
+            format!(
+                "[debug]\nstatic_binary = \"{}\"\n",
+                dir.join("rizzybox-static").display()
+            ),
+        )
+        .unwrap();
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.env("PATH", &dir)
+            .env("RIZZYBOX_CONFIG", dir.join("config.toml"))
+            .env("RZ_CONTAINER_ENGINE", "podman")
+            .args(["debug", "--dry-run", "--target", "web", "echo", "it's"]);
+
+        // Assert
+        cmd.assert().success().stdout(format!(
+            "podman run --rm -it --userns=container:web --user 0:0 --pid=container:web \
+             --network=container:web --ipc=container:web --volumes-from=web \
+             -v {}:/bin/rizzybox --entrypoint=/bin/rizzybox sha256:abc echo 'it'\\''s'\n",
+            dir.join("rizzybox-static").display()
+        ));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn refuses_a_dynamically_linked_rizzybox() {
+        // Arrange
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+        let rizzybox = PathBuf::from(cmd.get_program());
+        if Elf::read(&rizzybox)
+            .unwrap()
+            .interpreter()
+            .unwrap()
+            .is_none()
+        {
+            // a static build has nothing to refuse
+            return;
+        }
+
+        // Act
+        cmd.env("RIZZYBOX_CONFIG", "/dev/null")
+            .args(["debug", "--engine", "false", "alpine"]);
+
+        // Assert
+        cmd.assert()
+            .code(1)
+            .stderr(predicates::str::contains(format!(
+                "rizzybox: {} is dynamically linked against /",
+                rizzybox.display()
+            )));
+    }
+
+    #[test]
+    fn runs_the_engine_found_in_path() {
+        // Arrange
+        let dir = fake_engine("nerdctl");
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.env("PATH", &dir)
+            .env_remove("RZ_CONTAINER_ENGINE")
+            .args([
+                "debug",
+                "--allow-dynamic",
+                "--engine-arg",
+                "--pull=never",
+                "alpine",
+            ]);
+
+        // Assert
+        cmd.assert().code(3).stdout(predicates::str::ends_with(
+            "--entrypoint=/bin/rizzybox --pull=never alpine --install-self /tmp/rbin\n",
+        ));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "uname")]
+    fn finds_the_platform_among_engine_args() {
+        for (engine_args, arch) in [
+            (&["--platform=linux/arm64/v8"][..], Some("arm64")),
+            (
+                &["--pull=never", "--platform", "linux/amd64"],
+                Some("amd64"),
+            ),
+            (&["--platformx=linux/amd64"], None),
+            (&["--platform"], None),
+        ] {
+            // Arrange
+            let engine_args: Vec<OsString> = engine_args.iter().map(OsString::from).collect();
+
+            // Act
+            let found = platform_arch(&engine_args);
+
+            // Assert
+            assert_eq!(found.as_deref(), arch, "{engine_args:?}");
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "uname")]
+    fn mounts_a_cached_binary_for_another_architecture() {
+        // Arrange
+        let dir = fake_engine("docker");
+        write_static_binary(&dir.join("cache/rizzybox/riscv64/rizzybox"));
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.env("PATH", &dir)
+            .env("XDG_CACHE_HOME", dir.join("cache"))
+            .env("FAKE_ARCH", "riscv64")
+            .env_remove("RZ_CONTAINER_ENGINE")
+            .args(["debug", "--dry-run", "alpine"]);
+
+        // Assert
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains(format!(
+                " -v {}:/bin/rizzybox ",
+                dir.join("cache/rizzybox/riscv64/rizzybox").display()
+            )));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "uname")]
+    fn explains_a_missing_binary_for_another_architecture() {
+        // Arrange
+        let dir = fake_engine("docker");
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.env("PATH", &dir)
+            .env("XDG_CACHE_HOME", dir.join("cache"))
+            .env_remove("RZ_CONTAINER_ENGINE")
+            .args([
+                "debug",
+                "--dry-run",
+                "--engine-arg=--platform=linux/s390x",
+                "alpine",
+            ]);
+
+        // Assert
+        cmd.assert()
+            .code(1)
+            .stderr(predicates::str::contains(format!(
+                "the image is for s390x, but this rizzybox is for {} and there is no {}",
+                rizzybox::applets::uname::uname_report(rizzybox::applets::uname::IsaFormat::docker)
+                    .machine,
+                dir.join("cache/rizzybox/s390x/rizzybox").display()
+            )));
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
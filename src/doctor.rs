@@ -0,0 +1,245 @@
+use std::{
+    env,
+    ffi::OsStr,
+    fs::{self, File},
+    io::{BufRead, BufReader, Write},
+    os::unix::ffi::OsStrExt,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Result;
+use rizzybox::{applet::APPLETS, applets::which::which_matches, error::status};
+
+use crate::{
+    elf::Elf,
+    install::{MANIFEST_NAME, is_rizzybox},
+};
+
+/// What is wrong with an applet in an installation dir, if anything
+enum LinkState {
+    Ok,
+    Missing,
+    /// a symlink whose target is gone
+    Broken(PathBuf),
+    /// recorded in the manifest, but not this rizzybox; another build took its place
+    OtherRizzybox(Option<PathBuf>),
+    /// something that rizzybox didn't put there, e.g. kept by `--no-clobber`
+    Foreign,
+}
+
+/// Audit the installation in `dir`, or every one in `PATH` if not given: links that
+/// are broken or lead to another rizzybox, applets that other coreutils shadow in
+/// `PATH`, and whether `rizzybox` is statically linked. Returns 1 if anything is
+/// wrong.
+pub fn doctor(rizzybox: &Path, dir: Option<&Path>, out: &mut dyn Write) -> Result<i32> {
+    let mut problems = 0;
+
+    match Elf::read(rizzybox).and_then(|elf| elf.dependencies()) {
+        Ok(None) => writeln!(out, "{}: statically linked", rizzybox.display())?,
+        Ok(Some(dependencies)) => {
+            problems += 1;
+            writeln!(
+                out,
+                "{}: dynamically linked against {dependencies}; `debug` needs a static build to run in minimal images",
+                rizzybox.display(),
+            )?;
+        }
+        Err(e) => {
+            problems += 1;
+            writeln!(out, "{}: {e:#}", rizzybox.display())?;
+        }
+    }
+
+    let dirs = match dir {
+        Some(dir) => vec![dir.to_owned()],
+        None => installations(),
+    };
+    if dirs.is_empty() {
+        writeln!(
+            out,
+            "\nno installation with a {MANIFEST_NAME} found in PATH"
+        )?;
+    }
+    for dir in &dirs {
+        problems += audit_links(rizzybox, dir, out)?;
+    }
+
+    problems += audit_path(rizzybox, out)?;
+
+    if problems == 0 {
+        writeln!(out, "\nno problems found")?;
+        Ok(0)
+    } else {
+        writeln!(
+            out,
+            "\n{problems} problem{} found",
+            if problems == 1 { "" } else { "s" }
+        )?;
+        Ok(status::FAILURE)
+    }
+}
+
+/// The dirs in `PATH` that rizzybox has been installed into
+fn installations() -> Vec<PathBuf> {
+    let path = env::var_os("PATH").unwrap_or_default();
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    for dir in path.as_bytes().split(|&byte| byte == b':') {
+        let dir = PathBuf::from(OsStr::from_bytes(dir));
+        if dir.join(MANIFEST_NAME).is_file() && !dirs.contains(&dir) {
+            dirs.push(dir);
+        }
+    }
+    dirs
+}
+
+/// Report the applets in `dir` that are broken or lead elsewhere, returning how many
+fn audit_links(rizzybox: &Path, dir: &Path, out: &mut dyn Write) -> Result<usize> {
+    writeln!(out, "\n{}:", dir.display())?;
+    let recorded = recorded_targets(dir);
+
+    let mut problems = 0;
+    let mut ok = 0;
+    let mut missing = Vec::new();
+    for applet in APPLETS {
+        let target = dir.join(applet.name());
+        match link_state(rizzybox, &target, &recorded) {
+            LinkState::Ok => ok += 1,
+            LinkState::Missing => missing.push(applet.name()),
+            LinkState::Broken(to) => {
+                problems += 1;
+                writeln!(out, "  {}: broken link to {}", applet.name(), to.display())?;
+            }
+            LinkState::OtherRizzybox(Some(to)) => {
+                problems += 1;
+                writeln!(
+                    out,
+                    "  {}: points to a different rizzybox at {}",
+                    applet.name(),
+                    to.display()
+                )?;
+            }
+            LinkState::OtherRizzybox(None) => {
+                problems += 1;
+                writeln!(out, "  {}: is a different rizzybox", applet.name())?;
+            }
+            LinkState::Foreign => {
+                writeln!(out, "  {}: not rizzybox, left alone", applet.name())?;
+            }
+        }
+    }
+    writeln!(out, "  {ok} of {} applets ok", APPLETS.len())?;
+    if !missing.is_empty() {
+        writeln!(out, "  not installed: {}", missing.join(" "))?;
+    }
+    Ok(problems)
+}
+
+fn link_state(rizzybox: &Path, target: &Path, recorded: &[PathBuf]) -> LinkState {
+    let Ok(meta) = fs::symlink_metadata(target) else {
+        return LinkState::Missing;
+    };
+    let link = meta
+        .is_symlink()
+        .then(|| fs::read_link(target).ok())
+        .flatten();
+    if let Some(link) = &link
+        && fs::metadata(target).is_err()
+    {
+        return LinkState::Broken(link.clone());
+    }
+    if is_rizzybox(target, rizzybox) {
+        return LinkState::Ok;
+    }
+    if recorded.iter().any(|path| path == target) {
+        return LinkState::OtherRizzybox(link);
+    }
+    LinkState::Foreign
+}
+
+/// The paths the manifest in `dir` says were installed
+fn recorded_targets(dir: &Path) -> Vec<PathBuf> {
+    let Ok(file) = File::open(dir.join(MANIFEST_NAME)) else {
+        return Vec::new();
+    };
+    BufReader::new(file)
+        .split(b'\n')
+        .map_while(Result::ok)
+        .filter_map(|line| {
+            let tab = line.iter().position(|&byte| byte == b'\t')?;
+            Some(PathBuf::from(OsStr::from_bytes(&line[tab + 1..])))
+        })
+        .collect()
+}
+
+/// Report the applets that run something else when invoked through `PATH`, the way
+/// `which -a` finds them, returning how many
+fn audit_path(rizzybox: &Path, out: &mut dyn Write) -> Result<usize> {
+    writeln!(out, "\nPATH:")?;
+    let mut shadowed = 0;
+    let mut not_found = Vec::new();
+    for applet in APPLETS {
+        let matches = which_matches(true, applet.name())?;
+        match matches.iter().position(|path| is_rizzybox(path, rizzybox)) {
+            Some(0) => {}
+            Some(_) => {
+                shadowed += 1;
+                writeln!(
+                    out,
+                    "  {}: shadowed by {}",
+                    applet.name(),
+                    matches[0].display()
+                )?;
+            }
+            None => not_found.push(applet.name()),
+        }
+    }
+    if shadowed == 0 {
+        writeln!(out, "  no applets are shadowed")?;
+    }
+    if !not_found.is_empty() {
+        writeln!(out, "  not in PATH: {}", not_found.join(" "))?;
+    }
+    Ok(shadowed)
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_cmd::Command;
+    use std::{fs, os::unix::fs::symlink};
+
+    #[test]
+    fn reports_broken_and_shadowed_applets() {
+        // Arrange
+        let root = std::env::temp_dir().join(format!("rizzybox-doctor-{}", std::process::id()));
+        let (bin, other) = (root.join("bin"), root.join("other"));
+        let _ = fs::remove_dir_all(&root);
+        Command::cargo_bin(env!("CARGO_PKG_NAME"))
+            .unwrap()
+            .arg("--install-self")
+            .arg(&bin)
+            .assert()
+            .success();
+        fs::remove_file(bin.join("sh")).unwrap();
+        symlink(root.join("gone"), bin.join("sh")).unwrap();
+        fs::create_dir_all(&other).unwrap();
+        fs::write(other.join("mkdir"), "").unwrap();
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.arg("doctor")
+            .env("PATH", format!("{}:{}", other.display(), bin.display()));
+
+        // Assert
+        cmd.assert()
+            .code(1)
+            .stdout(predicates::str::contains(format!(
+                "  sh: broken link to {}\n",
+                root.join("gone").display()
+            )))
+            .stdout(predicates::str::contains(format!(
+                "  mkdir: shadowed by {}\n",
+                other.join("mkdir").display()
+            )));
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,346 @@
+use std::{
+    ffi::{OsStr, OsString},
+    fmt, fs,
+    os::unix::ffi::OsStrExt,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result, bail};
+
+/// Program header type of a segment that is mapped into memory
+const PT_LOAD: u32 = 1;
+/// Program header type of the segment that lists what the dynamic loader needs
+const PT_DYNAMIC: u32 = 2;
+/// Program header type of the segment that names the dynamic loader
+const PT_INTERP: u32 = 3;
+
+/// Dynamic section tag that ends the section
+const DT_NULL: u64 = 0;
+/// Dynamic section tag of a library the binary needs, as an offset into the string table
+const DT_NEEDED: u64 = 1;
+/// Dynamic section tag of the string table's address
+const DT_STRTAB: u64 = 5;
+
+/// An ELF binary, read far enough to tell how it is linked
+pub struct Elf {
+    data: Vec<u8>,
+    is_64: bool,
+    little_endian: bool,
+}
+
+/// The parts of a program header that matter here
+struct ProgramHeader {
+    kind: u32,
+    offset: u64,
+    address: u64,
+    file_size: u64,
+}
+
+/// What a dynamically linked binary needs from the system it runs on
+pub struct Dependencies {
+    pub interpreter: PathBuf,
+    /// the libraries it names, e.g. `libc.so.6`
+    pub needed: Vec<OsString>,
+}
+
+impl fmt::Display for Dependencies {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.interpreter.display())?;
+        for library in &self.needed {
+            write!(f, ", {}", library.display())?;
+        }
+        Ok(())
+    }
+}
+
+impl Elf {
+    /// Read the ELF binary at `path`
+    pub fn read(path: &Path) -> Result<Self> {
+        let data = fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+        Self::parse(data).with_context(|| format!("failed to inspect {}", path.display()))
+    }
+
+    /// Check that `data` is an ELF file this module understands
+    pub fn parse(data: Vec<u8>) -> Result<Self> {
+        if data.get(..4) != Some(b"\x7fELF") {
+            bail!("not an ELF file");
+        }
+        let is_64 = match data.get(4) {
+            Some(1) => false,
+            Some(2) => true,
+            _ => bail!("unknown ELF class"),
+        };
+        let little_endian = match data.get(5) {
+            Some(1) => true,
+            Some(2) => false,
+            _ => bail!("unknown ELF byte order"),
+        };
+        Ok(Self {
+            data,
+            is_64,
+            little_endian,
+        })
+    }
+
+    /// The dynamic loader the binary asks for, or `None` if it is statically linked.
+    /// Static PIEs have a dynamic section, but no loader.
+    pub fn interpreter(&self) -> Result<Option<PathBuf>> {
+        let Some(header) = self
+            .program_headers()?
+            .into_iter()
+            .find(|header| header.kind == PT_INTERP)
+        else {
+            return Ok(None);
+        };
+        let path = self.bytes(header.offset, header.file_size)?;
+        // the path is NUL-terminated
+        let path = path.split(|&byte| byte == 0).next().unwrap_or_default();
+        Ok(Some(PathBuf::from(OsStr::from_bytes(path))))
+    }
+
+    /// The libraries the binary names in its dynamic section, in order. Static PIEs
+    /// have a dynamic section without any.
+    pub fn needed(&self) -> Result<Vec<OsString>> {
+        let headers = self.program_headers()?;
+        let Some(dynamic) = headers.iter().find(|header| header.kind == PT_DYNAMIC) else {
+            return Ok(Vec::new());
+        };
+
+        let entry_size = if self.is_64 { 16 } else { 8 };
+        let mut needed = Vec::new();
+        let mut string_table = None;
+        for index in 0..dynamic.file_size / entry_size {
+            let start = offset_of(dynamic.offset, index, entry_size)?;
+            let (tag, value) = (
+                self.uint(start, entry_size / 2)?,
+                self.uint(offset_of(start, 1, entry_size / 2)?, entry_size / 2)?,
+            );
+            match tag {
+                DT_NULL => break,
+                DT_NEEDED => needed.push(value),
+                DT_STRTAB => string_table = Some(value),
+                _ => {}
+            }
+        }
+        if needed.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // the string table is given by its address, so find where it is in the file
+        let address = string_table.context("dynamic section without a string table")?;
+        let table = headers
+            .iter()
+            .find(|header| {
+                header.kind == PT_LOAD
+                    && header
+                        .address
+                        .checked_add(header.file_size)
+                        .is_some_and(|end| (header.address..end).contains(&address))
+            })
+            .context("string table outside of the binary")?;
+        let table_offset = offset_of(table.offset, 1, address - table.address)?;
+        let table_end = offset_of(table.offset, 1, table.file_size)?;
+        needed
+            .into_iter()
+            .map(|name| {
+                let start = offset_of(table_offset, 1, name)?;
+                let name = self.bytes(start, table_end.saturating_sub(start))?;
+                let name = name.split(|&byte| byte == 0).next().unwrap_or_default();
+                Ok(OsStr::from_bytes(name).to_owned())
+            })
+            .collect()
+    }
+
+    /// The loader and libraries the binary needs, or `None` if it is statically linked
+    pub fn dependencies(&self) -> Result<Option<Dependencies>> {
+        let Some(interpreter) = self.interpreter()? else {
+            return Ok(None);
+        };
+        Ok(Some(Dependencies {
+            interpreter,
+            needed: self.needed()?,
+        }))
+    }
+
+    fn program_headers(&self) -> Result<Vec<ProgramHeader>> {
+        let (offset, entry_size, count) = if self.is_64 {
+            (
+                self.uint(0x20, 8)?,
+                self.uint(0x36, 2)?,
+                self.uint(0x38, 2)?,
+            )
+        } else {
+            (
+                self.uint(0x1c, 4)?,
+                self.uint(0x2a, 2)?,
+                self.uint(0x2c, 2)?,
+            )
+        };
+
+        (0..count)
+            .map(|index| {
+                let start = offset_of(offset, index, entry_size)?;
+                let field = |at, size| self.uint(offset_of(start, 1, at)?, size);
+                Ok(if self.is_64 {
+                    ProgramHeader {
+                        kind: field(0, 4)? as u32,
+                        offset: field(8, 8)?,
+                        address: field(16, 8)?,
+                        file_size: field(32, 8)?,
+                    }
+                } else {
+                    ProgramHeader {
+                        kind: field(0, 4)? as u32,
+                        offset: field(4, 4)?,
+                        address: field(8, 4)?,
+                        file_size: field(16, 4)?,
+                    }
+                })
+            })
+            .collect()
+    }
+
+    /// The `size` bytes at `offset`
+    fn bytes(&self, offset: u64, size: u64) -> Result<&[u8]> {
+        usize::try_from(offset)
+            .ok()
+            .zip(usize::try_from(size).ok())
+            .and_then(|(start, size)| self.data.get(start..start.checked_add(size)?))
+            .context("truncated ELF file")
+    }
+
+    /// The unsigned integer of `size` bytes at `offset`, in the binary's byte order
+    fn uint(&self, offset: u64, size: u64) -> Result<u64> {
+        let bytes = self.bytes(offset, size)?;
+        let fold = |value: u64, &byte: &u8| value << 8 | u64::from(byte);
+        Ok(if self.little_endian {
+            bytes.iter().rev().fold(0, fold)
+        } else {
+            bytes.iter().fold(0, fold)
+        })
+    }
+}
+
+/// `base + index * size`, failing when the offsets in the binary overflow it
+fn offset_of(base: u64, index: u64, size: u64) -> Result<u64> {
+    index
+        .checked_mul(size)
+        .and_then(|offset| base.checked_add(offset))
+        .context("malformed ELF file")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Elf;
+
+    /// A 64-bit little-endian ELF header followed by a program header for each of
+    /// `segments`, given as their kind, address and contents
+    fn elf_with_segments(segments: &[(u32, u64, &[u8])]) -> Vec<u8> {
+        let mut data = vec![0; 64 + 56 * segments.len()];
+        data[..6].copy_from_slice(b"\x7fELF\x02\x01");
+        data[0x20..0x28].copy_from_slice(&64u64.to_le_bytes()); // e_phoff
+        data[0x36..0x38].copy_from_slice(&56u16.to_le_bytes()); // e_phentsize
+        data[0x38..0x3a].copy_from_slice(&(segments.len() as u16).to_le_bytes()); // e_phnum
+        for (index, (kind, address, payload)) in segments.iter().enumerate() {
+            let header = 64 + 56 * index;
+            let payload_offset = data.len() as u64;
+            data[header..header + 4].copy_from_slice(&kind.to_le_bytes());
+            data[header + 8..header + 16].copy_from_slice(&payload_offset.to_le_bytes());
+            data[header + 16..header + 24].copy_from_slice(&address.to_le_bytes());
+            data[header + 32..header + 40].copy_from_slice(&(payload.len() as u64).to_le_bytes());
+            data.extend_from_slice(payload);
+        }
+        data
+    }
+
+    /// A dynamic section of `(tag, value)` entries
+    fn dynamic_section(entries: &[(u64, u64)]) -> Vec<u8> {
+        entries
+            .iter()
+            .flat_map(|(tag, value)| [tag.to_le_bytes(), value.to_le_bytes()])
+            .flatten()
+            .collect()
+    }
+
+    #[test]
+    fn finds_the_interpreter() {
+        // Arrange
+        let data = elf_with_segments(&[(3, 0, b"/lib/ld-musl-x86_64.so.1\0")]);
+
+        // Act
+        let elf = Elf::parse(data).unwrap();
+
+        // Assert
+        assert_eq!(
+            elf.interpreter().unwrap().unwrap().to_str(),
+            Some("/lib/ld-musl-x86_64.so.1")
+        );
+    }
+
+    #[test]
+    fn static_without_interpreter() {
+        // Arrange
+        let data = elf_with_segments(&[(1, 0, b"")]);
+
+        // Act
+        let elf = Elf::parse(data).unwrap();
+
+        // Assert
+        assert!(elf.interpreter().unwrap().is_none());
+        assert!(elf.dependencies().unwrap().is_none());
+    }
+
+    #[test]
+    fn lists_needed_libraries() {
+        // Arrange
+        let strings = b"\0libc.so.6\0libm.so.6\0";
+        let dynamic = dynamic_section(&[(1, 1), (1, 11), (5, 0x4000), (0, 0)]);
+        let data = elf_with_segments(&[
+            (3, 0, b"/lib64/ld-linux-x86-64.so.2\0"),
+            (1, 0x4000, strings),
+            (2, 0x5000, &dynamic),
+        ]);
+
+        // Act
+        let dependencies = Elf::parse(data).unwrap().dependencies().unwrap().unwrap();
+
+        // Assert
+        assert_eq!(
+            dependencies.to_string(),
+            "/lib64/ld-linux-x86-64.so.2, libc.so.6, libm.so.6"
+        );
+    }
+
+    #[test]
+    fn rejects_offsets_that_overflow() {
+        // Arrange
+        let dynamic = dynamic_section(&[(1, 1), (5, u64::MAX - 1), (0, 0)]);
+        let wrapping_table = elf_with_segments(&[
+            (3, 0, b"/lib/ld-musl-x86_64.so.1\0"),
+            (1, u64::MAX - 2, b"\0libc.so\0"),
+            (2, 0x5000, &dynamic),
+        ]);
+        let mut wrapping_headers = elf_with_segments(&[(3, 0, b"")]);
+        wrapping_headers[0x20..0x28].copy_from_slice(&u64::MAX.to_le_bytes());
+
+        // Act
+        let wrapping_table = Elf::parse(wrapping_table).unwrap().dependencies();
+        let wrapping_headers = Elf::parse(wrapping_headers).unwrap().interpreter();
+
+        // Assert
+        assert!(wrapping_table.is_err());
+        assert!(wrapping_headers.is_err());
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        // Arrange
+        let data = b"#!/bin/sh\n".to_vec();
+
+        // Act
+        let result = Elf::parse(data);
+
+        // Assert
+        assert!(result.is_err());
+    }
+}
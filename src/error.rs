@@ -0,0 +1,154 @@
+use std::{fmt, io};
+
+/// Exit statuses that coreutils agree on
+pub mod status {
+    /// the applet failed, or failed for some of its operands
+    pub const FAILURE: i32 = 1;
+    /// serious trouble, like `ls` not being able to access an operand, or misuse of
+    /// rizzybox's own arguments
+    pub const TROUBLE: i32 = 2;
+    /// an applet that runs a command, like `env`, failed before it could
+    pub const CANNOT_RUN: i32 = 125;
+    /// the command was found but could not be invoked
+    pub const CANNOT_INVOKE: i32 = 126;
+    /// the command was not found
+    pub const NOT_FOUND: i32 = 127;
+    /// the reader of the output went away; what shells report for a process that
+    /// was killed by `SIGPIPE`
+    pub const BROKEN_PIPE: i32 = 128 + 13;
+}
+
+/// An error reported the way coreutils report them: `applet: target: reason`.
+/// The applet's name is added by whoever prints it.
+#[derive(Debug)]
+pub struct Error {
+    target: Option<String>,
+    reason: String,
+    status: i32,
+}
+
+impl Error {
+    /// An error that isn't about any operand in particular
+    pub fn new(reason: impl fmt::Display) -> Self {
+        Self {
+            target: None,
+            reason: reason.to_string(),
+            status: status::FAILURE,
+        }
+    }
+
+    /// An I/O error on `target`, e.g. `cat: missing: No such file or directory`
+    pub fn io(target: impl fmt::Display, error: &io::Error) -> Self {
+        Self {
+            target: Some(target.to_string()),
+            reason: reason(error),
+            status: status::FAILURE,
+        }
+    }
+
+    /// Exit with `status` instead of [`status::FAILURE`]
+    #[must_use]
+    pub fn with_status(mut self, status: i32) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// The status the applet should exit with
+    pub fn status(&self) -> i32 {
+        self.status
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.target {
+            Some(target) => write!(f, "{target}: {}", self.reason),
+            None => write!(f, "{}", self.reason),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Describe an I/O error the way libc's `strerror` does, without the
+/// ` (os error N)` that std adds
+pub fn reason(error: &io::Error) -> String {
+    let message = error.to_string();
+    match message.rfind(" (os error ") {
+        Some(end) => message[..end].to_owned(),
+        None => message,
+    }
+}
+
+/// Mark `error` as having happened while writing output, so that it is reported as
+/// `write error: reason` instead of being blamed on whatever was being read
+pub fn write_error(error: io::Error) -> io::Error {
+    if is_write_error(&error) {
+        return error;
+    }
+    let kind = error.kind();
+    io::Error::new(kind, Error::io("write error", &error))
+}
+
+/// Whether `error` happened while writing output, see [`write_error`]
+pub fn is_write_error(error: &io::Error) -> bool {
+    error.get_ref().is_some_and(|inner| inner.is::<Error>())
+}
+
+/// The status to exit with for `error`, and the message to print after the applet's
+/// name. A broken pipe on the output only means that nobody wants the rest of it,
+/// so there is nothing to print for it. One on the input is reported like any
+/// other error.
+pub fn describe(error: &anyhow::Error) -> (i32, Option<String>) {
+    let broken_pipe = error
+        .chain()
+        .filter_map(|e| e.downcast_ref::<io::Error>())
+        .any(|e| e.kind() == io::ErrorKind::BrokenPipe && is_write_error(e));
+    if broken_pipe {
+        return (status::BROKEN_PIPE, None);
+    }
+
+    if let Some(e) = error.downcast_ref::<Error>() {
+        (e.status(), Some(e.to_string()))
+    } else if let Some(e) = error.downcast_ref::<io::Error>() {
+        // a write error carries its message along, see [`write_error`]
+        let message = match e.get_ref().and_then(|inner| inner.downcast_ref::<Error>()) {
+            Some(inner) => inner.to_string(),
+            None => reason(e),
+        };
+        (status::FAILURE, Some(message))
+    } else {
+        (status::FAILURE, Some(format!("{error:#}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use super::{describe, status, write_error};
+
+    #[test]
+    fn broken_pipe_on_output_is_quiet() {
+        // Arrange
+        let error = write_error(io::Error::from(io::ErrorKind::BrokenPipe));
+
+        // Act
+        let described = describe(&error.into());
+
+        // Assert
+        assert_eq!(described, (status::BROKEN_PIPE, None));
+    }
+
+    #[test]
+    fn broken_pipe_on_input_is_reported() {
+        // Arrange
+        let error = anyhow::Error::new(io::Error::from(io::ErrorKind::BrokenPipe));
+
+        // Act
+        let described = describe(&error);
+
+        // Assert
+        assert_eq!(described, (status::FAILURE, Some("broken pipe".to_owned())));
+    }
+}
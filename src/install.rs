@@ -0,0 +1,587 @@
+use std::{
+    ffi::OsStr,
+    fs::{self, File},
+    io::{BufRead, BufReader, Read, Write},
+    os::unix::{
+        ffi::OsStrExt,
+        fs::{MetadataExt, symlink},
+    },
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use clap_complete::Shell;
+use rizzybox::applet::APPLETS;
+
+use crate::{elf::Elf, manpages};
+
+/// Name of the file in the installation dir that records what was installed
+pub const MANIFEST_NAME: &str = ".rizzybox-manifest";
+
+/// How man pages are recorded in the manifest, in place of an [`InstallMode`]
+const MANPAGE_ENTRY: &str = "manpage";
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+/// How `--install-self` puts applets in place
+pub enum InstallMode {
+    /// symlink each applet to rizzybox
+    #[default]
+    Symlink,
+    /// hardlink each applet to rizzybox; the dir must be on the same filesystem
+    Hardlink,
+    /// copy rizzybox to each applet
+    Copy,
+}
+
+impl std::fmt::Display for InstallMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            InstallMode::Symlink => "symlink",
+            InstallMode::Hardlink => "hardlink",
+            InstallMode::Copy => "copy",
+        };
+        write!(f, "{name}")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+/// The shells that `--install` can print a script for
+pub enum InstallShell {
+    Bash,
+    Zsh,
+    Fish,
+    Nu,
+    #[value(alias = "pwsh")]
+    Powershell,
+}
+
+impl InstallShell {
+    /// Detect the user's shell from `$SHELL`, the same way `completions` does
+    pub fn from_env() -> Option<Self> {
+        match Shell::from_env() {
+            Some(Shell::Bash) => Some(Self::Bash),
+            Some(Shell::Zsh) => Some(Self::Zsh),
+            Some(Shell::Fish) => Some(Self::Fish),
+            Some(Shell::PowerShell) => Some(Self::Powershell),
+            // clap_complete doesn't know about these
+            _ => match std::env::var_os("SHELL")
+                .as_deref()
+                .and_then(|shell| Path::new(shell).file_stem())
+                .and_then(|name| name.to_str())
+            {
+                Some("nu") => Some(Self::Nu),
+                Some("pwsh") => Some(Self::Powershell),
+                _ => None,
+            },
+        }
+    }
+
+    /// Quote `s` as a literal string argument
+    fn quote(self, s: &str) -> String {
+        match self {
+            Self::Bash | Self::Zsh => format!("'{}'", s.replace('\'', r"'\''")),
+            Self::Fish => format!("'{}'", s.replace('\\', r"\\").replace('\'', r"\'")),
+            Self::Nu => format!("\"{}\"", s.replace('\\', r"\\").replace('"', "\\\"")),
+            Self::Powershell => format!("'{}'", s.replace('\'', "''")),
+        }
+    }
+}
+
+pub struct InstallOptions {
+    pub mode: InstallMode,
+    /// skip files that already exist and aren't rizzybox
+    pub no_clobber: bool,
+    /// only print what would be done
+    pub dry_run: bool,
+    /// where to write man pages, if anywhere
+    pub man_dir: Option<PathBuf>,
+}
+
+/// Install every applet into `dir` and record them in its manifest, writing what
+/// would be done to `out` on a dry run and warnings to `err`
+pub fn install(
+    rizzybox: &Path,
+    dir: &Path,
+    options: &InstallOptions,
+    out: &mut dyn Write,
+    err: &mut dyn Write,
+) -> Result<()> {
+    if !options.dry_run {
+        fs::create_dir_all(dir).with_context(|| format!("failed to create {}", dir.display()))?;
+    }
+    // links keep working with the libraries next to rizzybox, copies may travel
+    if options.mode == InstallMode::Copy
+        && let Ok(Some(dependencies)) = Elf::read(rizzybox).and_then(|elf| elf.dependencies())
+    {
+        writeln!(
+            err,
+            "warning: {} is dynamically linked; the copies in {} only run where {dependencies} exist",
+            rizzybox.display(),
+            dir.display()
+        )?;
+    }
+
+    let mut installed = Vec::new();
+    for applet in APPLETS {
+        let target = dir.join(applet.name());
+
+        if fs::symlink_metadata(&target).is_ok() {
+            if options.no_clobber && !is_rizzybox(&target, rizzybox) {
+                writeln!(
+                    err,
+                    "skipping {}: it exists and is not rizzybox",
+                    target.display()
+                )?;
+                continue;
+            }
+            if options.dry_run {
+                writeln!(out, "would replace {}", target.display())?;
+            } else {
+                fs::remove_file(&target)
+                    .with_context(|| format!("failed to remove {}", target.display()))?;
+            }
+        }
+
+        if options.dry_run {
+            writeln!(
+                out,
+                "would {} {} to {}",
+                options.mode,
+                target.display(),
+                rizzybox.display()
+            )?;
+        } else {
+            match options.mode {
+                InstallMode::Symlink => symlink(rizzybox, &target),
+                InstallMode::Hardlink => fs::hard_link(rizzybox, &target),
+                InstallMode::Copy => fs::copy(rizzybox, &target).map(|_| ()),
+            }
+            .with_context(|| format!("failed to {} {}", options.mode, target.display()))?;
+        }
+        installed.push((options.mode.to_string(), target));
+    }
+
+    if let Some(man_dir) = &options.man_dir {
+        if options.dry_run {
+            for (name, _) in manpages::pages()? {
+                writeln!(out, "would write {}", man_dir.join(name).display())?;
+            }
+        } else {
+            for page in manpages::generate_to(man_dir)? {
+                installed.push((MANPAGE_ENTRY.to_owned(), page));
+            }
+        }
+    }
+
+    if !options.dry_run {
+        write_manifest(rizzybox, dir, &installed)?;
+    }
+    Ok(())
+}
+
+/// Print a script that links every applet into an installation dir of the user's
+/// choosing, in the syntax of `shell`. `sudo` is prefixed to each privileged command.
+pub fn install_script(
+    shell: InstallShell,
+    rizzybox: &Path,
+    sudo: &str,
+    out: &mut dyn Write,
+) -> Result<()> {
+    let rizzybox = shell.quote(&rizzybox.display().to_string());
+    let default_dir = shell.quote("/usr/local/bin");
+
+    writeln!(
+        out,
+        "# to install rizzybox bins, paste the following in your shell:\n"
+    )?;
+    match shell {
+        InstallShell::Bash | InstallShell::Zsh => writeln!(
+            out,
+            "RIZZYBOX_INSTALL_DIR={default_dir} # change this to the desired installation path"
+        )?,
+        InstallShell::Fish => writeln!(
+            out,
+            "set RIZZYBOX_INSTALL_DIR {default_dir} # change this to the desired installation path"
+        )?,
+        InstallShell::Nu => writeln!(
+            out,
+            "let rizzybox_install_dir = {default_dir} # change this to the desired installation path"
+        )?,
+        InstallShell::Powershell => writeln!(
+            out,
+            "$RizzyboxInstallDir = {default_dir} # change this to the desired installation path"
+        )?,
+    }
+
+    for applet in APPLETS {
+        let name = applet.name();
+        match shell {
+            InstallShell::Bash | InstallShell::Zsh => writeln!(
+                out,
+                "{sudo}ln -sf {rizzybox} \"$RIZZYBOX_INSTALL_DIR/{name}\""
+            )?,
+            InstallShell::Fish => {
+                writeln!(out, "{sudo}ln -sf {rizzybox} $RIZZYBOX_INSTALL_DIR/{name}")?
+            }
+            InstallShell::Nu => writeln!(
+                out,
+                "^{sudo}ln -sf {rizzybox} ($rizzybox_install_dir | path join {})",
+                shell.quote(name)
+            )?,
+            // New-Item can't be run through sudo, so fall back to ln in that case
+            InstallShell::Powershell if !sudo.is_empty() => writeln!(
+                out,
+                "{sudo}ln -sf {rizzybox} (Join-Path $RizzyboxInstallDir {})",
+                shell.quote(name)
+            )?,
+            InstallShell::Powershell => writeln!(
+                out,
+                "New-Item -ItemType SymbolicLink -Force -Path (Join-Path $RizzyboxInstallDir {}) -Target {rizzybox} | Out-Null",
+                shell.quote(name)
+            )?,
+        }
+    }
+
+    writeln!(
+        out,
+        "\n# optionally, uncomment the following to add the installation dir to PATH:"
+    )?;
+    match shell {
+        InstallShell::Bash => writeln!(
+            out,
+            r#"# case ":$PATH:" in *":$RIZZYBOX_INSTALL_DIR:"*) ;; *) export PATH="$PATH:$RIZZYBOX_INSTALL_DIR" ;; esac"#
+        )?,
+        InstallShell::Zsh => {
+            writeln!(out, r#"# typeset -U path; path+=("$RIZZYBOX_INSTALL_DIR")"#)?
+        }
+        InstallShell::Fish => writeln!(out, "# fish_add_path $RIZZYBOX_INSTALL_DIR")?,
+        InstallShell::Nu => writeln!(
+            out,
+            "# $env.PATH = ($env.PATH | append $rizzybox_install_dir | uniq)"
+        )?,
+        InstallShell::Powershell => writeln!(
+            out,
+            "# if (($env:PATH -split [IO.Path]::PathSeparator) -notcontains $RizzyboxInstallDir) {{ $env:PATH += [IO.Path]::PathSeparator + $RizzyboxInstallDir }}"
+        )?,
+    }
+    Ok(())
+}
+
+/// Remove the applets in `dir` that point back to `rizzybox`, leaving anything else
+/// alone, and writing what would be done to `out` on a dry run
+pub fn uninstall(
+    rizzybox: &Path,
+    dir: &Path,
+    dry_run: bool,
+    out: &mut dyn Write,
+    err: &mut dyn Write,
+) -> Result<()> {
+    let manifest = dir.join(MANIFEST_NAME);
+
+    let mut candidates: Vec<PathBuf> = APPLETS
+        .iter()
+        .map(|applet| dir.join(applet.name()))
+        .collect();
+    let mut pages = Vec::new();
+    // the manifest may list applets that this build of rizzybox doesn't include
+    if let Ok(file) = File::open(&manifest) {
+        for line in BufReader::new(file).split(b'\n') {
+            let line = line?;
+            let Some(tab) = line.iter().position(|&byte| byte == b'\t') else {
+                continue;
+            };
+            let path = PathBuf::from(OsStr::from_bytes(&line[tab + 1..]));
+            if &line[..tab] == MANPAGE_ENTRY.as_bytes() {
+                pages.push(path);
+            } else if !candidates.contains(&path) {
+                candidates.push(path);
+            }
+        }
+    }
+
+    let candidates = candidates
+        .into_iter()
+        .map(|target| {
+            let ours = is_rizzybox(&target, rizzybox);
+            (target, ours)
+        })
+        .chain(pages.into_iter().map(|page| {
+            let ours = is_rizzybox_manpage(&page);
+            (page, ours)
+        }));
+    for (target, ours) in candidates {
+        if fs::symlink_metadata(&target).is_err() {
+            continue;
+        }
+        if !ours {
+            writeln!(err, "skipping {}: it is not rizzybox", target.display())?;
+            continue;
+        }
+        if dry_run {
+            writeln!(out, "would remove {}", target.display())?;
+        } else {
+            fs::remove_file(&target)
+                .with_context(|| format!("failed to remove {}", target.display()))?;
+        }
+    }
+
+    if fs::symlink_metadata(&manifest).is_ok() {
+        if dry_run {
+            writeln!(out, "would remove {}", manifest.display())?;
+        } else {
+            fs::remove_file(&manifest)
+                .with_context(|| format!("failed to remove {}", manifest.display()))?;
+        }
+    }
+    Ok(())
+}
+
+fn write_manifest(rizzybox: &Path, dir: &Path, installed: &[(String, PathBuf)]) -> Result<()> {
+    let manifest = dir.join(MANIFEST_NAME);
+    let mut file = File::create(&manifest)
+        .with_context(|| format!("failed to write {}", manifest.display()))?;
+
+    writeln!(
+        file,
+        "# installed by rizzybox {} from {}",
+        env!("CARGO_PKG_VERSION"),
+        rizzybox.display()
+    )?;
+    // paths are written byte for byte so that they can be read back as they were
+    for (mode, target) in installed {
+        write!(file, "{mode}\t")?;
+        file.write_all(target.as_os_str().as_bytes())?;
+        writeln!(file)?;
+    }
+    Ok(())
+}
+
+/// Whether `path` is a symlink or hardlink to `rizzybox`, or a copy of it
+pub fn is_rizzybox(path: &Path, rizzybox: &Path) -> bool {
+    let (Ok(meta), Ok(rizzybox_meta)) = (fs::metadata(path), fs::metadata(rizzybox)) else {
+        return false;
+    };
+    if meta.dev() == rizzybox_meta.dev() && meta.ino() == rizzybox_meta.ino() {
+        return true;
+    }
+    meta.len() == rizzybox_meta.len() && same_contents(path, rizzybox).unwrap_or(false)
+}
+
+/// Whether `path` is a man page that rizzybox wrote, going by the source on its `.TH` line
+fn is_rizzybox_manpage(path: &Path) -> bool {
+    let Ok(file) = File::open(path) else {
+        return false;
+    };
+    BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .find(|line| line.starts_with(".TH "))
+        .is_some_and(|title| title.contains("rizzybox "))
+}
+
+fn same_contents(a: &Path, b: &Path) -> std::io::Result<bool> {
+    let (mut a, mut b) = (
+        BufReader::new(File::open(a)?),
+        BufReader::new(File::open(b)?),
+    );
+    let (mut buf_a, mut buf_b) = ([0; 8192], [0; 8192]);
+    loop {
+        let read = a.read(&mut buf_a)?;
+        if read == 0 {
+            return Ok(b.read(&mut buf_b)? == 0);
+        }
+        b.read_exact(&mut buf_b[..read])?;
+        if buf_a[..read] != buf_b[..read] {
+            return Ok(false);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_cmd::{Command, cargo::cargo_bin, prelude::*};
+    use std::{fs, path::PathBuf};
+
+    use super::MANIFEST_NAME;
+
+    /// A fresh, empty dir for one test to install into
+    fn install_dir(test: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("rizzybox-{test}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn install_script_for_fish() {
+        // Arrange
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.args(["--install", "--shell", "fish"]);
+
+        // Assert
+        cmd.assert().success();
+        cmd.assert()
+            .stdout(predicates::str::contains(
+                "\nset RIZZYBOX_INSTALL_DIR '/usr/local/bin'",
+            ))
+            .stdout(predicates::str::contains("$RIZZYBOX_INSTALL_DIR/sh\n"))
+            .stdout(predicates::str::contains(
+                "# fish_add_path $RIZZYBOX_INSTALL_DIR",
+            ));
+    }
+
+    #[test]
+    fn install_script_detects_shell() {
+        // Arrange
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.arg("--install").env("SHELL", "/usr/bin/nu");
+
+        // Assert
+        cmd.assert().success();
+        cmd.assert()
+            .stdout(predicates::str::contains("\nlet rizzybox_install_dir = "))
+            .stdout(predicates::str::contains(
+                "($rizzybox_install_dir | path join \"sh\")",
+            ));
+    }
+
+    #[test]
+    fn install_self_symlinks_and_writes_manifest() {
+        // Arrange
+        let dir = install_dir("symlink");
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.arg("--install-self").arg(&dir);
+
+        // Assert
+        cmd.assert().success();
+        assert!(fs::symlink_metadata(dir.join("sh")).unwrap().is_symlink());
+        let manifest = fs::read_to_string(dir.join(MANIFEST_NAME)).unwrap();
+        assert!(manifest.contains(&format!("symlink\t{}", dir.join("sh").display())));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn install_self_no_clobber_keeps_foreign_files() {
+        // Arrange
+        let dir = install_dir("no-clobber");
+        fs::write(dir.join("sh"), "not rizzybox").unwrap();
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.arg("--install-self").arg(&dir).arg("--no-clobber");
+
+        // Assert
+        cmd.assert().success();
+        assert_eq!(fs::read_to_string(dir.join("sh")).unwrap(), "not rizzybox");
+        assert!(
+            fs::symlink_metadata(dir.join("which"))
+                .unwrap()
+                .is_symlink()
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn install_self_dry_run_changes_nothing() {
+        // Arrange
+        let dir = install_dir("dry-run");
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.arg("--install-self").arg(&dir).arg("--dry-run");
+
+        // Assert
+        cmd.assert().success();
+        cmd.assert().stdout(predicates::str::contains(format!(
+            "would symlink {}",
+            dir.join("sh").display()
+        )));
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn install_self_dry_run_stops_at_closed_pipe() {
+        // Arrange
+        let dir = install_dir("dry-run-pipe");
+        let mut cmd = std::process::Command::new(cargo_bin(env!("CARGO_PKG_NAME")));
+
+        // Act
+        cmd.arg("--install-self")
+            .arg(&dir)
+            .arg("--dry-run")
+            .stdout(rizzybox::closed_pipe());
+
+        // Assert
+        cmd.assert()
+            .code(rizzybox::error::status::BROKEN_PIPE)
+            .stderr("");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn install_self_with_man_dir_writes_and_removes_pages() {
+        // Arrange
+        let dir = install_dir("man-dir");
+        let man_dir = dir.join("man1");
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.arg("--install-self")
+            .arg(&dir)
+            .arg("--man-dir")
+            .arg(&man_dir);
+
+        // Assert
+        cmd.assert().success();
+        assert!(man_dir.join("rizzybox.1").is_file());
+        let manifest = fs::read_to_string(dir.join(MANIFEST_NAME)).unwrap();
+        assert!(manifest.contains(&format!("manpage\t{}", man_dir.join("sh.1").display())));
+
+        Command::cargo_bin(env!("CARGO_PKG_NAME"))
+            .unwrap()
+            .arg("--install-self")
+            .arg(&dir)
+            .arg("--uninstall")
+            .assert()
+            .success();
+        assert_eq!(fs::read_dir(&man_dir).unwrap().count(), 0);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn uninstall_removes_only_rizzybox() {
+        // Arrange
+        let dir = install_dir("uninstall");
+        Command::cargo_bin(env!("CARGO_PKG_NAME"))
+            .unwrap()
+            .arg("--install-self")
+            .arg(&dir)
+            .arg("--mode")
+            .arg("copy")
+            .assert()
+            .success();
+        fs::remove_file(dir.join("which")).unwrap();
+        fs::write(dir.join("which"), "not rizzybox").unwrap();
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.arg("--install-self").arg(&dir).arg("--uninstall");
+
+        // Assert
+        cmd.assert().success();
+        let left: Vec<_> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(left, ["which"]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
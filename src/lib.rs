@@ -1,50 +1,11 @@
-use std::{fs::remove_file, string::String};
-
-pub mod consts {
-    /// Binaries that can be installed with `--install`
-    /// Example: `ln -sf /full/path/to/rizzybox /usr/local/bin/cat`
-    pub const INSTALLABLE_BINS: [&str; 21] = [
-        "arch",
-        "basename",
-        "cat",
-        "clear",
-        "dirname",
-        "echo",
-        "env",
-        "expand",
-        "false",
-        "ln",
-        "ls",
-        "mkdir",
-        "nproc",
-        "pathmunge",
-        "sh",
-        "sleep",
-        "stem",
-        "true",
-        "uname",
-        "which",
-        "yes",
-    ];
-}
+pub mod applet;
+pub mod applets;
+pub mod config;
+pub mod error;
+#[cfg(any(feature = "grep", feature = "sed"))]
+pub mod regex;
 
-/// A simple error handler with formatting
-pub fn handle_error<T>(result: Result<T, std::io::Error>, message: &str) -> T {
-    match result {
-        Ok(value) => value,
-        Err(e) => {
-            eprintln!(
-                "{}: {}: {}",
-                std::env::current_exe()
-                    .unwrap_or(env!("CARGO_PKG_NAME").into())
-                    .display(),
-                message,
-                e
-            );
-            std::process::exit(1);
-        }
-    }
-}
+use std::{fs::remove_file, string::String};
 
 /// # Errors
 ///
@@ -75,3 +36,11 @@ impl Drop for TestCleanup {
         }
     }
 }
+
+/// The writing end of a pipe whose reader is already gone, for testing how applets
+/// handle a broken pipe without racing the reader.
+/// ONLY USE THIS FOR TESTS!
+pub fn closed_pipe() -> std::io::PipeWriter {
+    let (_, writer) = std::io::pipe().expect("failed to create a pipe");
+    writer
+}
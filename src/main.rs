@@ -1,72 +1,125 @@
 mod cli;
-mod command;
+mod debug;
+mod doctor;
+mod elf;
+mod install;
+mod manpages;
+mod plugin;
 
 use std::{
     env::{self, current_exe},
+    ffi::OsString,
     fs::File,
-    path::PathBuf,
-    process::Command,
+    io::{self, Write, sink},
+    os::unix::ffi::{OsStrExt, OsStringExt},
+    path::Path,
 };
 
 use anyhow::{Context, Result, bail};
-use clap::{CommandFactory, Parser};
+use clap::FromArgMatches;
 use clap_complete::Shell;
-use rizzybox::consts::INSTALLABLE_BINS;
-
-const VERSION: &str = env!("CARGO_PKG_VERSION");
-
-use {
-    cli::{Cli, Commands},
-    command::{
-        basename::basename_command,
-        cat::cat_command,
-        clear::clear_command,
-        dirname::dirname_command,
-        echo::echo_command,
-        env::env_command,
-        expand::expand_command,
-        r#false::false_command,
-        ln::ln_command,
-        ls::ls_command,
-        mkdir::mkdir_command,
-        nproc::nproc_command,
-        pathmunge::pathmunge_command,
+use rizzybox::{
+    applet::{self, APPLETS},
+    applets::{
+        Output, Streams,
+        mkdir::{MkdirArgs, mkdir_command},
         sh::sh_command,
-        sleep::sleep_command,
-        stem::stem_command,
-        r#true::true_command,
-        uname::{arch_command, uname_command},
         which::which_command,
-        yes::yes_command,
+        write_json,
     },
+    config,
+    error::{self, status},
+};
+use serde::Serialize;
+
+use {
+    cli::{Cli, Commands, ConfigCommand},
+    install::{InstallOptions, InstallShell},
+    plugin::PluginCall,
 };
 
-#[expect(clippy::too_many_lines)]
-fn main() -> Result<()> {
-    let args: Vec<String> = env::args().collect();
-    let binary_name = args.first().map(String::as_str).unwrap_or_default();
+fn main() {
+    if let Err(e) = run() {
+        let (status, message) = error::describe(&e);
+        if let Some(message) = message {
+            eprintln!("{}: {message}", env!("CARGO_PKG_NAME"));
+        }
+        std::process::exit(status);
+    }
+}
+
+fn run() -> Result<()> {
+    // arguments are passed on as they are, whether or not they are valid UTF-8
+    let args: Vec<OsString> = env::args_os().collect();
+    let config = match config::init() {
+        Ok(config) => config,
+        // a broken config file shouldn't take every applet down with it
+        Err(e) if !checks_config(&args) => {
+            eprintln!(
+                "{}: warning: using the default configuration: {}",
+                env!("CARGO_PKG_NAME"),
+                format!("{e:#}").trim_end()
+            );
+            config::get()
+        }
+        Err(e) => return Err(e),
+    };
+    let binary_name = args.first().map(OsString::as_os_str).unwrap_or_default();
+
+    // run by the Docker CLI as a plugin: `docker-debug [docker options] debug IMAGE`
+    let plugin_call = PluginCall::parse(&args)?;
+    if let Some(call) = &plugin_call {
+        call.check_installed(binary_name)?;
+        call.apply_env();
+    }
 
     // determine if invoked as subcommand directly: `/bin/echo`
-    let args = if INSTALLABLE_BINS
-        .iter()
-        .any(|&bin| binary_name.ends_with(bin))
-    {
-        // shift binary name to subcommand name
-        let subcommand_name = binary_name.split('/').next_back().unwrap_or(binary_name);
-        let mut new_args = vec![binary_name.to_string(), subcommand_name.to_string()];
-        new_args.extend(args.into_iter().skip(1));
-        new_args
-    } else {
-        args
+    let mut args = match plugin_call {
+        Some(call) => {
+            let mut new_args = vec![binary_name.to_owned(), call.subcommand.into()];
+            new_args.extend(call.args);
+            new_args
+        }
+        None => match applet::resolve_argv0(binary_name) {
+            Some(subcommand) => {
+                // shift binary name to subcommand name
+                let mut new_args = vec![binary_name.to_owned()];
+                new_args.extend(subcommand.into_iter().map(OsString::from));
+                new_args.extend(args.iter().skip(1).cloned());
+                new_args
+            }
+            None => args.clone(),
+        },
     };
+    config.expand_args(&mut args, 1);
+    if let Some(applet) = args
+        .get(1)
+        .and_then(|name| name.to_str())
+        .and_then(applet::find)
+    {
+        applet.rewrite_args(&mut args, 1);
+    }
 
-    let cli = Cli::parse_from(args);
+    let matches = match cli::command().try_get_matches_from(&args) {
+        Ok(matches) => matches,
+        Err(e) => {
+            let _ = e.print();
+            let name = args.get(1).and_then(|name| name.to_str());
+            let status = match name.and_then(applet::find) {
+                _ if !e.use_stderr() => 0,
+                Some(applet) => applet.usage_status(),
+                None => status::TROUBLE,
+            };
+            std::process::exit(status);
+        }
+    };
+    let cli = Cli::from_arg_matches(&matches)?;
 
     let mut sudo_str = "";
     if cli.install_with_sudo {
-        sudo_str = if which_command(false, "doas", true)?.is_some() {
+        sudo_str = if which_command(false, "doas", true, &mut sink())?.is_some() {
             "doas "
-        } else if which_command(false, "sudo", true)?.is_some() {
+        } else if which_command(false, "sudo", true, &mut sink())?.is_some() {
             "sudo "
         } else {
             bail!(
@@ -76,280 +129,185 @@ fn main() -> Result<()> {
     }
 
     if cli.install | cli.install_with_sudo {
-        println!("# to install rizzybox bins, paste the following in your shell:\n");
-        // `export` works with more shells, like fish
-        println!(
-            "export RIZZYBOX_INSTALL_DIR=/usr/local/bin # change this to the desired installation path"
-        );
-        for bin in INSTALLABLE_BINS {
-            println!(
-                "{sudo_str}ln -sf {} $RIZZYBOX_INSTALL_DIR/{bin}",
-                std::env::current_exe()
-                    .context("rizzybox should exist")?
-                    .display(),
-            );
-        }
+        // POSIX syntax works in most shells when we can't tell which one is in use
+        let shell = cli
+            .shell
+            .or_else(InstallShell::from_env)
+            .unwrap_or(InstallShell::Bash);
+        let rizzybox = current_exe().context("rizzybox should exist")?;
+        install::install_script(shell, &rizzybox, sudo_str, &mut Output(io::stdout()))?;
         return Ok(());
     }
 
     if let Some(installation_dir) = cli.install_self {
-        let path = current_exe().expect("failed to get path to current executable");
-        let binary_name = path.to_str().expect("binary name should be valid unicode");
+        let rizzybox = current_exe().context("rizzybox should exist")?;
+        let options = InstallOptions {
+            mode: cli.mode,
+            no_clobber: cli.no_clobber,
+            dry_run: cli.dry_run,
+            man_dir: cli.man_dir,
+        };
 
-        // assume that the existence of /.dockerenv means we're running in a container
-        if File::open("/.dockerenv").is_ok() {
+        if cli.uninstall {
+            install::uninstall(
+                &rizzybox,
+                &installation_dir,
+                cli.dry_run,
+                &mut Output(io::stdout()),
+                &mut io::stderr(),
+            )?;
+        } else if File::open("/.dockerenv").is_ok() && !cli.dry_run {
+            // assume that the existence of /.dockerenv means we're running in a container.
             // create a dir for the symlinks and add it to PATH so that we don't conflict
             // with any bins that may exist in the image
-            let rbin_dir = vec![PathBuf::from(installation_dir.to_string())];
-            mkdir_command(rbin_dir, true)?;
-
-            let mut path = std::env::var("PATH")
-                .unwrap_or(
-                    "/usr/local/sbin:/usr/local/bin:/usr/sbin:/usr/bin:/sbin:/bin".to_owned(),
-                )
-                .trim_end_matches(':')
-                .to_owned();
-            path.push_str(&format!(":{installation_dir}"));
-            unsafe { std::env::set_var("PATH", path) };
-
-            for bin in INSTALLABLE_BINS {
-                ln_command(
-                    true,
-                    true,
-                    binary_name,
-                    &format!("{installation_dir}/{bin}"),
-                )?;
+            let rbin_dir = MkdirArgs {
+                dirs: vec![installation_dir.clone()],
+                parents: true,
+            };
+            if mkdir_command(&rbin_dir, &mut io::stderr())? != 0 {
+                std::process::exit(status::FAILURE);
+            }
+
+            let mut path = std::env::var_os("PATH")
+                .unwrap_or_else(|| {
+                    "/usr/local/sbin:/usr/local/bin:/usr/sbin:/usr/bin:/sbin:/bin".into()
+                })
+                .into_vec();
+            while path.last() == Some(&b':') {
+                path.pop();
             }
+            path.push(b':');
+            path.extend_from_slice(installation_dir.as_os_str().as_bytes());
+            unsafe { std::env::set_var("PATH", OsString::from_vec(path)) };
+
+            install::install(
+                &rizzybox,
+                &installation_dir,
+                &options,
+                &mut Output(io::stdout()),
+                &mut io::stderr(),
+            )?;
             // drop into an interactive shell session
-            sh_command()?;
+            std::process::exit(sh_command(&mut Streams::inherit())?);
         } else {
-            // we're not running in a container, so just create the symlinks
+            // we're not running in a container, so just create the links
             // where specified
-            for bin in INSTALLABLE_BINS {
-                ln_command(
-                    true,
-                    true,
-                    binary_name,
-                    &format!("{installation_dir}/{bin}"),
-                )?;
-            }
+            install::install(
+                &rizzybox,
+                &installation_dir,
+                &options,
+                &mut Output(io::stdout()),
+                &mut io::stderr(),
+            )?;
         }
     }
 
-    if cli.list {
+    if cli.list && cli.json {
+        let applets: Vec<ListedApplet> = APPLETS
+            .iter()
+            .map(|applet| ListedApplet {
+                name: applet.name(),
+                aliases: applet.aliases(),
+            })
+            .collect();
+        write_json(&applets, &mut Output(io::stdout()))?;
+    } else if cli.list {
         let mut print_str = String::new();
-        for bin in INSTALLABLE_BINS {
-            print_str.push_str(bin);
+        for applet in APPLETS {
+            print_str.push_str(applet.name());
             print_str.push(' ');
         }
-        println!("{}", print_str.trim_end());
+        writeln!(Output(io::stdout()), "{}", print_str.trim_end())?;
     }
 
-    if let Some(command) = cli.command {
-        match command {
-            Commands::Arch {} => {
-                arch_command();
-            }
-            Commands::Basename {
-                multiple,
-                name,
-                suffix,
-                zero,
-            } => {
-                basename_command(multiple, &name, suffix.as_ref(), zero);
-            }
-            Commands::Cat {
-                file,
-                language,
-                theme,
-                show_all,
-                list_themes,
-                number_lines,
-            } => {
-                cat_command(
-                    &file,
-                    &language,
-                    &theme,
-                    show_all,
-                    list_themes,
-                    number_lines,
-                );
-            }
-            Commands::Clear {} => clear_command(),
-            Commands::Completions { shell } => {
-                let Some(shell) = shell.or_else(Shell::from_env) else {
-                    bail!(
-                        "Couldn't automatically detect the shell. Run `{} completions --help` for more info.",
-                        std::env::args().collect::<Vec<String>>()[0]
-                    );
-                };
-                let mut cmd = Cli::command();
-                let name = cmd.get_name().to_string();
-                clap_complete::generate(shell, &mut cmd, name, &mut std::io::stdout());
-            }
-            Commands::Debug { command } | Commands::Rebug { command } => {
-                let path = current_exe().expect("failed to get path to current executable");
-                let binary_name = path.to_str().expect("binary name should be valid unicode");
-                let container_engine = if which_command(false, "docker", true)?.is_some() {
-                    "docker"
-                } else if which_command(false, "podman", true)?.is_some() {
-                    "podman"
-                } else {
-                    bail!(
-                        "No container engine was detected. Please ensure that one of (docker, podman) are available in PATH"
-                    )
-                };
-
-                let container_command = if command.len() > 1 {
-                    command // pass the user's `command` as an arg to rizzybox
-                } else {
-                    // invoke --install-self and drop into an interactive shell
-                    vec![
-                        command.iter().map(|c| c.to_owned()).collect(),
-                        "--install-self".to_owned(),
-                        "/tmp/rbin".to_owned(),
-                    ]
-                };
-
-                Command::new(container_engine)
-                    .args([
-                        "run",
-                        "--rm",
-                        "-it",
-                        "--user", // we need to be root to ensure symlinks can be created in /bin
-                        "0:0",
-                        "-v",
-                        &format!("{binary_name}:/bin/rizzybox"),
-                        "--entrypoint=/bin/rizzybox",
-                    ])
-                    .args(container_command)
-                    .status()?;
-            }
-            Commands::Dirname { name, zero } => {
-                dirname_command(&name, zero);
-            }
-            Commands::DockerCliPluginMetadata {} => {
-                println!(
-                    r#"{{ "SchemaVersion": "0.1.0", "Vendor": "If You're Reading This, You Shouldn't Be", "Version": "{VERSION}", "ShortDescription": "Poor folks' docker-debug" }}"#
-                );
-            }
-            Commands::Echo {
-                disable_backslash_escapes,
-                enable_backslash_escapes,
-                language,
-                nonewline,
-                string: text,
-                theme,
-            } => {
-                echo_command(
-                    disable_backslash_escapes,
-                    enable_backslash_escapes,
-                    &language,
-                    nonewline,
-                    &text,
-                    &theme,
-                )?;
-            }
-            Commands::Env {
-                argv0,
-                chdir,
-                command,
-                ignore_environment,
-                null,
-                unset,
-                kv_pair,
-            } => {
-                env_command(
-                    argv0.as_ref(),
-                    chdir.as_ref(),
-                    &command,
-                    ignore_environment,
-                    null,
-                    &unset,
-                    &kv_pair,
-                )?;
+    if let Some((name, subcommand_matches)) = matches.subcommand() {
+        let mut streams = Streams::inherit();
+        let status = match applet::find(name) {
+            Some(applet) => {
+                let result = applet
+                    .run(subcommand_matches, &mut streams)
+                    .and_then(|status| Ok(streams.stdout.flush().map(|()| status)?));
+                applet::exit_status(applet, result, &mut streams.stderr)
             }
-            Commands::False {} => false_command(),
-            Commands::Expand { file, tabs } => {
-                expand_command(&file, &tabs)?;
+            None => {
+                let status = execute(Commands::from_arg_matches(&matches)?, &mut streams)?;
+                streams.stdout.flush()?;
+                status
             }
-            Commands::Ln {
-                force,
-                symlink,
-                source,
-                destination,
-            } => {
-                ln_command(force, symlink, &source, &destination)?;
-            }
-            Commands::Ls { all, path } => {
-                ls_command(all, &path)?;
-            }
-            Commands::Mkdir { dirs, parents } => {
-                mkdir_command(dirs, parents)?;
-            }
-            Commands::Nproc {
-                all,
-                ignore,
-                omp_num_limit,
-                omp_num_threads,
-            } => nproc_command(all, ignore, omp_num_limit, omp_num_threads),
-            Commands::Pathmunge { command } => pathmunge_command(command),
-            Commands::Sh {} => {
-                sh_command()?;
-            }
-            Commands::Sleep { number } => {
-                sleep_command(&number)?;
-            }
-            Commands::Stem { nonewline, words } => {
-                stem_command(nonewline, &words);
-            }
-            Commands::True {} => {
-                true_command();
-            }
-            Commands::Uname {
-                all,
-                kernel,
-                nodename,
-                kernel_release,
-                kernel_version,
-                machine,
-                operating_system,
-                isa_format,
-            } => {
-                uname_command(
-                    all,
-                    kernel,
-                    nodename,
-                    kernel_release,
-                    kernel_version,
-                    machine,
-                    operating_system,
-                    isa_format,
+        };
+        if status != 0 {
+            std::process::exit(status);
+        }
+    }
+    Ok(())
+}
+
+/// An applet as `--list --json` prints it
+#[derive(Serialize)]
+struct ListedApplet {
+    name: &'static str,
+    aliases: &'static [&'static str],
+}
+
+/// Run one of rizzybox's own subcommands against `streams`, returning its exit status
+fn execute(command: Commands, streams: &mut Streams) -> Result<i32> {
+    match command {
+        Commands::Completions {
+            shell,
+            prefix,
+            applets,
+        } => {
+            let Some(shell) = shell.or_else(Shell::from_env) else {
+                bail!(
+                    "Couldn't automatically detect the shell. Run `{} completions --help` for more info.",
+                    Path::new(&env::args_os().next().unwrap_or_default()).display()
                 );
+            };
+            cli::write_completions(shell, &prefix, applets, &mut streams.stdout)?;
+        }
+        Commands::Debug(args) | Commands::Rebug(args) => {
+            let rizzybox = current_exe().context("failed to get path to current executable")?;
+            return debug::debug(&rizzybox, &args, &mut streams.stdout, &mut streams.stderr);
+        }
+        Commands::Config {
+            command: ConfigCommand::Show,
+        } => {
+            match config::path() {
+                Some(path) if path.exists() => writeln!(streams.stdout, "# {}", path.display())?,
+                Some(path) => writeln!(streams.stdout, "# {} (not found)", path.display())?,
+                None => writeln!(streams.stdout, "# no config file")?,
             }
-            Commands::Which {
-                all_occurrences,
-                command,
-                silent,
-            } => {
-                let result = which_command(all_occurrences, &command, silent)?;
-                if result.is_none() {
-                    std::process::exit(1);
-                }
-            }
-            Commands::Yes {
-                text,
-                amount,
-                duration,
-            } => {
-                yes_command(&text, amount, duration);
-            }
+            write!(streams.stdout, "{}", config::get().show()?)?;
+        }
+        Commands::Doctor { dir } => {
+            let rizzybox = current_exe().context("failed to get path to current executable")?;
+            return doctor::doctor(&rizzybox, dir.as_deref(), &mut streams.stdout);
+        }
+        Commands::Manpages { dir } => {
+            manpages::generate_to(&dir)?;
+        }
+        Commands::DockerCliPluginMetadata {} => {
+            write_json(&plugin::METADATA, &mut streams.stdout)?;
         }
     }
-    Ok(())
+    Ok(0)
 }
 
-#[cfg(test)]
+/// Whether `args` run `rizzybox config` or `rizzybox doctor`, which are there to
+/// report a broken config file rather than to work around it
+fn checks_config(args: &[OsString]) -> bool {
+    let invoked_as = args
+        .first()
+        .and_then(|argv0| Path::new(argv0).file_name())
+        .and_then(|name| name.to_str());
+    invoked_as.is_some_and(|name| applet::MULTICALL_NAMES.contains(&name))
+        && matches!(
+            args.get(1).and_then(|arg| arg.to_str()),
+            Some("config" | "doctor")
+        )
+}
+
+#[cfg(all(test, feature = "echo"))]
 mod tests {
     use assert_cmd::Command;
     use rizzybox::TestCleanup;
@@ -359,6 +317,21 @@ mod tests {
         path::PathBuf,
     };
 
+    /// Symlink rizzybox as `name` in the temp dir, removing the link once the cleanup is dropped
+    fn symlink_rizzybox(name: &str) -> (String, TestCleanup) {
+        let rizzybox_cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+        let rizzybox_path = PathBuf::from(rizzybox_cmd.get_program());
+
+        let symlink_path = env::temp_dir().join(name);
+        let _ = symlink(rizzybox_path, &symlink_path);
+        let symlinked_bin = symlink_path.to_string_lossy().to_string();
+
+        let cleanup = TestCleanup {
+            file: Some(symlinked_bin.clone()),
+        };
+        (symlinked_bin, cleanup)
+    }
+
     /// tests the ability to invoke `rizzybox COMMAND` as `COMMAND` directly
     #[test]
     fn test_argshift() {
@@ -372,17 +345,7 @@ mod tests {
         // }
 
         // Arrange
-        let rizzybox_cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
-        let rizzybox_path = PathBuf::from(rizzybox_cmd.get_program());
-
-        let temp_dir = env::temp_dir();
-        let symlink_path = temp_dir.join("echo");
-        let _ = symlink(rizzybox_path, &symlink_path);
-        let symlinked_bin = symlink_path.to_string_lossy().to_string();
-
-        let _cleanup = TestCleanup {
-            file: Some(symlinked_bin.clone()),
-        };
+        let (symlinked_bin, _cleanup) = symlink_rizzybox("echo");
 
         // Act
         let mut cmd = Command::new(&symlinked_bin);
@@ -393,4 +356,84 @@ mod tests {
         cmd.assert()
             .stdout("using `echo` like a normal binary B-)\n");
     }
+
+    /// a name that merely ends with an applet's name is not that applet
+    #[test]
+    fn test_argshift_requires_exact_name() {
+        // Arrange
+        let (symlinked_bin, _cleanup) = symlink_rizzybox("notecho");
+
+        // Act
+        let mut cmd = Command::new(&symlinked_bin);
+        cmd.args(["echo", "still rizzybox"]);
+
+        // Assert
+        cmd.assert().success();
+        cmd.assert().stdout("still rizzybox\n");
+    }
+
+    #[test]
+    fn test_argshift_with_prefix() {
+        for name in ["rz-echo", "rizzybox-echo"] {
+            // Arrange
+            let (symlinked_bin, _cleanup) = symlink_rizzybox(name);
+
+            // Act
+            let mut cmd = Command::new(&symlinked_bin);
+            cmd.arg("prefixed");
+
+            // Assert
+            cmd.assert().success();
+            cmd.assert().stdout("prefixed\n");
+        }
+    }
+
+    #[test]
+    fn test_argshift_with_configured_prefix() {
+        // Arrange
+        let (symlinked_bin, _cleanup) = symlink_rizzybox("my-echo");
+
+        // Act
+        let mut cmd = Command::new(&symlinked_bin);
+        cmd.env("RIZZYBOX_PREFIXES", "foo-:my-").arg("configured");
+
+        // Assert
+        cmd.assert().success();
+        cmd.assert().stdout("configured\n");
+    }
+
+    #[test]
+    fn test_argshift_as_multicall_name() {
+        for name in ["busybox", "docker-debug"] {
+            // Arrange
+            let (symlinked_bin, _cleanup) = symlink_rizzybox(name);
+
+            // Act
+            let mut cmd = Command::new(&symlinked_bin);
+            cmd.args(["echo", "multicall"]);
+
+            // Assert
+            cmd.assert().success();
+            cmd.assert().stdout("multicall\n");
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "ls")]
+    fn test_argshift_with_alias() {
+        // Arrange
+        let (symlinked_bin, _cleanup) = symlink_rizzybox("ll");
+        let dir = env::temp_dir().join(format!("rizzybox-ll-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join(".hidden"), "").unwrap();
+
+        // Act
+        let mut cmd = Command::new(&symlinked_bin);
+        cmd.arg(&dir);
+
+        // Assert
+        cmd.assert().success();
+        cmd.assert().stdout(predicates::str::contains(".hidden"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
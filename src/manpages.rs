@@ -0,0 +1,114 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use clap_mangen::{
+    Man,
+    roff::{Roff, roman},
+};
+use rizzybox::applet::{self, APPLETS};
+
+use crate::cli;
+
+/// What the `.TH` line of every page names as its source, which is how
+/// `--uninstall` tells the pages rizzybox wrote apart from anyone else's
+pub const SOURCE: &str = concat!("rizzybox ", env!("CARGO_PKG_VERSION"));
+
+/// Render `rizzybox(1)` and one page per applet, as pairs of file name and roff
+pub fn pages() -> Result<Vec<(String, Vec<u8>)>> {
+    let mut pages = vec![("rizzybox.1".to_owned(), rizzybox_page()?)];
+    for applet in APPLETS {
+        let man = Man::new(applet::command(*applet)).source(SOURCE);
+        let mut page = Vec::new();
+        man.render(&mut page)?;
+        pages.push((man.get_filename(), page));
+    }
+    Ok(pages)
+}
+
+/// Write every page into `dir`, returning the paths written
+pub fn generate_to(dir: &Path) -> Result<Vec<PathBuf>> {
+    fs::create_dir_all(dir).with_context(|| format!("failed to create {}", dir.display()))?;
+
+    let mut written = Vec::new();
+    for (name, page) in pages()? {
+        let path = dir.join(name);
+        fs::write(&path, page).with_context(|| format!("failed to write {}", path.display()))?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+/// `rizzybox(1)`. Rendered section by section, since clap_mangen would reflow the
+/// banner in `long_about` into a single paragraph.
+fn rizzybox_page() -> Result<Vec<u8>> {
+    let mut cmd = cli::command();
+    cmd.build();
+    let man = Man::new(cmd.clone()).source(SOURCE);
+
+    let mut description = Roff::default();
+    description.control("SH", ["DESCRIPTION"]);
+    if let Some(about) = cmd.get_about() {
+        description.text([roman(about.to_string())]);
+    }
+    if let Some(banner) = cmd.get_long_about() {
+        description.control("PP", []).control("nf", []);
+        for line in banner.to_string().trim_matches('\n').lines() {
+            description.text([roman(line)]);
+        }
+        description.control("fi", []);
+    }
+
+    let mut page = Vec::new();
+    man.render_title(&mut page)?;
+    page.extend(section(|w| man.render_name_section(w))?);
+    page.extend(section(|w| man.render_synopsis_section(w))?);
+    page.extend(section(|w| description.to_writer(w))?);
+    page.extend(section(|w| man.render_options_section(w))?);
+    page.extend(section(|w| man.render_subcommands_section(w))?);
+    page.extend(section(|w| man.render_version_section(w))?);
+    Ok(page)
+}
+
+/// Render one section of a page. Every render starts with the same preamble, which
+/// only needs to be there once, at the top of the page.
+fn section(render: impl FnOnce(&mut Vec<u8>) -> io::Result<()>) -> io::Result<Vec<u8>> {
+    let mut section = Vec::new();
+    render(&mut section)?;
+    let preamble = Roff::default().render();
+    Ok(match section.strip_prefix(preamble.as_bytes()) {
+        Some(rest) => rest.to_vec(),
+        None => section,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_cmd::Command;
+    use std::fs;
+
+    use rizzybox::applet::APPLETS;
+
+    #[test]
+    fn writes_a_page_per_applet() {
+        // Arrange
+        let dir = std::env::temp_dir().join(format!("rizzybox-manpages-{}", std::process::id()));
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.arg("manpages").arg(&dir);
+
+        // Assert
+        cmd.assert().success();
+        let rizzybox = fs::read_to_string(dir.join("rizzybox.1")).unwrap();
+        assert!(rizzybox.starts_with(".ie"));
+        assert!(rizzybox.contains("\n.nf\n8888888b."));
+        for applet in APPLETS {
+            let page = fs::read_to_string(dir.join(format!("{}.1", applet.name()))).unwrap();
+            assert!(page.contains(&format!(".TH {} 1", applet.name())));
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
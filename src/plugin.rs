@@ -0,0 +1,459 @@
+use std::{
+    env,
+    ffi::{OsStr, OsString},
+    os::unix::ffi::OsStrExt,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Result, bail};
+use rizzybox::applet::MULTICALL_NAMES;
+use serde::Serialize;
+
+/// What the Docker CLI sets to its own path when it runs a plugin
+const DOCKER_CLI_ENV: &str = "DOCKER_CLI_PLUGIN_ORIGINAL_CLI_COMMAND";
+
+/// The subcommand the Docker CLI asks every plugin for its metadata with
+pub const METADATA_SUBCOMMAND: &str = "docker-cli-plugin-metadata";
+
+/// Options of the `docker` CLI that it passes to plugins ahead of their own name,
+/// with the environment variable each one stands for, if any
+const GLOBAL_OPTIONS: &[GlobalOption] = &[
+    GlobalOption::value("--config", None, Some("DOCKER_CONFIG")),
+    GlobalOption::value("--context", Some("-c"), Some("DOCKER_CONTEXT")),
+    GlobalOption::value("--host", Some("-H"), Some("DOCKER_HOST")),
+    GlobalOption::value("--log-level", Some("-l"), None),
+    GlobalOption::cert("--tlscacert", "ca.pem"),
+    GlobalOption::cert("--tlscert", "cert.pem"),
+    GlobalOption::cert("--tlskey", "key.pem"),
+    GlobalOption::flag("--debug", Some("-D"), None),
+    GlobalOption::flag("--tls", None, Some("DOCKER_TLS")),
+    GlobalOption::flag("--tlsverify", None, Some("DOCKER_TLS_VERIFY")),
+];
+
+/// The variable that names the dir docker reads the TLS files of [`GLOBAL_OPTIONS`] from
+const DOCKER_CERT_PATH: &str = "DOCKER_CERT_PATH";
+
+struct GlobalOption {
+    long: &'static str,
+    short: Option<&'static str>,
+    env: Option<&'static str>,
+    takes_value: bool,
+    /// the name the file given to a TLS option has in `DOCKER_CERT_PATH`
+    cert_file: Option<&'static str>,
+}
+
+impl GlobalOption {
+    const fn value(
+        long: &'static str,
+        short: Option<&'static str>,
+        env: Option<&'static str>,
+    ) -> Self {
+        Self {
+            long,
+            short,
+            env,
+            takes_value: true,
+            cert_file: None,
+        }
+    }
+
+    const fn cert(long: &'static str, cert_file: &'static str) -> Self {
+        Self {
+            long,
+            short: None,
+            env: None,
+            takes_value: true,
+            cert_file: Some(cert_file),
+        }
+    }
+
+    const fn flag(
+        long: &'static str,
+        short: Option<&'static str>,
+        env: Option<&'static str>,
+    ) -> Self {
+        Self {
+            long,
+            short,
+            env,
+            takes_value: false,
+            cert_file: None,
+        }
+    }
+}
+
+/// What a plugin answers `docker-cli-plugin-metadata` with
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Metadata {
+    schema_version: &'static str,
+    vendor: &'static str,
+    version: &'static str,
+    short_description: &'static str,
+    #[serde(rename = "URL")]
+    url: &'static str,
+}
+
+pub const METADATA: Metadata = Metadata {
+    schema_version: "0.1.0",
+    vendor: "rizzybox",
+    version: env!("CARGO_PKG_VERSION"),
+    short_description: "Debug minimal container images with a rizzybox shell",
+    url: env!("CARGO_PKG_REPOSITORY"),
+};
+
+/// rizzybox being run by the Docker CLI as the plugin `docker-NAME`
+#[derive(Debug, PartialEq)]
+pub struct PluginCall {
+    /// the rizzybox subcommand to run: `NAME`, or the metadata request
+    pub subcommand: String,
+    /// the arguments after it
+    pub args: Vec<OsString>,
+    /// variables to set for the container engine, from docker's own options
+    pub env: Vec<(&'static str, OsString)>,
+}
+
+impl PluginCall {
+    /// The call in `args` when rizzybox is run as one of the `docker-` [`MULTICALL_NAMES`]
+    /// and docker's options are followed by the plugin's name or the metadata request,
+    /// e.g. `docker-debug --context remote debug alpine`. Anything else, like
+    /// `docker-debug echo hi`, is an ordinary multicall invocation. Fails on TLS files
+    /// that can't be handed on through `DOCKER_CERT_PATH`.
+    pub fn parse(args: &[OsString]) -> Result<Option<Self>> {
+        let Some(plugin) = args
+            .first()
+            .and_then(|argv0| Path::new(argv0).file_name()?.to_str())
+            .filter(|name| MULTICALL_NAMES.contains(name))
+            .and_then(|name| name.strip_prefix("docker-"))
+        else {
+            return Ok(None);
+        };
+
+        let mut env = Vec::new();
+        let mut certs = Vec::new();
+        let mut rest = args[1..].iter();
+        while let Some(arg) = rest.next() {
+            if arg == plugin || arg == METADATA_SUBCOMMAND {
+                let Some(subcommand) = arg.to_str() else {
+                    return Ok(None);
+                };
+                if let Some(dir) = cert_dir(&certs)? {
+                    env.push((DOCKER_CERT_PATH, dir.into_os_string()));
+                }
+                return Ok(Some(Self {
+                    subcommand: subcommand.to_owned(),
+                    args: rest.cloned().collect(),
+                    env,
+                }));
+            }
+
+            let arg = arg.as_bytes();
+            let (flag, inline_value) = if arg.starts_with(b"--") {
+                match arg.iter().position(|&byte| byte == b'=') {
+                    Some(equals) => (&arg[..equals], Some(&arg[equals + 1..])),
+                    None => (arg, None),
+                }
+            } else {
+                // short options may have their value attached, as in `-Htcp://host:2375`
+                let (flag, value) = arg.split_at(arg.len().min(2));
+                let value = value.strip_prefix(b"=").unwrap_or(value);
+                (flag, (!value.is_empty()).then_some(value))
+            };
+            let Some(option) = GLOBAL_OPTIONS.iter().find(|option| {
+                flag == option.long.as_bytes() || Some(flag) == option.short.map(str::as_bytes)
+            }) else {
+                return Ok(None);
+            };
+            let value = match (option.takes_value, inline_value) {
+                (true, Some(value)) => OsStr::from_bytes(value).to_owned(),
+                (true, None) => match rest.next() {
+                    Some(value) => value.clone(),
+                    None => return Ok(None),
+                },
+                (false, None) => "1".into(),
+                // a bool given as `--tlsverify=false`, but not a bundle like `-Dl`
+                (false, Some(value)) if flag.starts_with(b"--") => match value {
+                    b"1" | b"t" | b"T" | b"true" | b"TRUE" | b"True" => "1".into(),
+                    b"0" | b"f" | b"F" | b"false" | b"FALSE" | b"False" => continue,
+                    _ => return Ok(None),
+                },
+                (false, Some(_)) => return Ok(None),
+            };
+            if let Some(var) = option.env {
+                env.push((var, value));
+            } else if let Some(file) = option.cert_file {
+                certs.push((option.long, file, PathBuf::from(value)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Check that the Docker CLI can find rizzybox as a plugin: either it ran us, or we
+    /// sit in a `cli-plugins` dir, where docker looks for them
+    pub fn check_installed(&self, argv0: &OsStr) -> Result<()> {
+        if self.subcommand == METADATA_SUBCOMMAND || env::var_os(DOCKER_CLI_ENV).is_some() {
+            return Ok(());
+        }
+        let path = Path::new(argv0);
+        if path.parent().and_then(Path::file_name) == Some(OsStr::new("cli-plugins")) {
+            return Ok(());
+        }
+        let name = path.file_name().unwrap_or_default().display();
+        bail!(
+            "{name} is not installed in a cli-plugins directory, so `docker {}` won't find it. \
+             Link it as ~/.docker/cli-plugins/{name}, or run `rizzybox {}` instead",
+            self.subcommand,
+            self.subcommand
+        )
+    }
+
+    /// Hand docker's options to the container engine through its environment, and
+    /// make the Docker CLI that ran us the engine unless one was picked already
+    pub fn apply_env(&self) {
+        for (var, value) in &self.env {
+            unsafe { env::set_var(var, value) };
+        }
+        if env::var_os("RZ_CONTAINER_ENGINE").is_none()
+            && let Some(docker) = env::var_os(DOCKER_CLI_ENV)
+        {
+            unsafe { env::set_var("RZ_CONTAINER_ENGINE", docker) };
+        }
+    }
+}
+
+/// The dir to hand the TLS files in `certs` on as `DOCKER_CERT_PATH`, as long as they are
+/// all in one, under the names docker looks for there
+fn cert_dir(certs: &[(&str, &str, PathBuf)]) -> Result<Option<PathBuf>> {
+    let mut dir = None;
+    for (option, file, path) in certs {
+        let parent = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        if path.file_name() != Some(OsStr::new(file)) || dir.is_some_and(|dir: &Path| dir != parent)
+        {
+            bail!(
+                "{option} {} can't be passed on to the container engine, which only reads \
+                 ca.pem, cert.pem and key.pem from the dir in {DOCKER_CERT_PATH}. Set \
+                 {DOCKER_CERT_PATH} to a dir holding them instead",
+                path.display()
+            );
+        }
+        dir = Some(parent);
+    }
+    Ok(dir.map(Path::to_path_buf))
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_cmd::Command;
+    use std::{
+        env,
+        ffi::OsString,
+        fs,
+        os::unix::fs::{PermissionsExt, symlink},
+        path::PathBuf,
+    };
+
+    use super::PluginCall;
+
+    fn args(args: &[&str]) -> Vec<OsString> {
+        args.iter().map(OsString::from).collect()
+    }
+
+    /// Link rizzybox as `name` in a new dir called `dir`, returning the link and the
+    /// temp dir to remove afterwards
+    fn link_rizzybox(dir: &str, name: &str) -> (PathBuf, PathBuf) {
+        let root = env::temp_dir().join(format!("rizzybox-plugin-{}-{dir}", std::process::id()));
+        let link = root.join(dir).join(name);
+        fs::create_dir_all(link.parent().unwrap()).unwrap();
+        let rizzybox = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+        symlink(rizzybox.get_program(), &link).unwrap();
+        (link, root)
+    }
+
+    #[test]
+    fn turns_docker_options_into_environment() {
+        // Arrange
+        let args = args(&[
+            "/usr/lib/docker/cli-plugins/docker-debug",
+            "--context=remote",
+            "-H",
+            "tcp://example:2375",
+            "--debug",
+            "--tlsverify",
+            "debug",
+            "--target",
+            "web",
+        ]);
+
+        // Act
+        let call = PluginCall::parse(&args).unwrap();
+
+        // Assert
+        assert_eq!(
+            call,
+            Some(PluginCall {
+                subcommand: "debug".to_owned(),
+                args: vec!["--target".into(), "web".into()],
+                env: vec![
+                    ("DOCKER_CONTEXT", "remote".into()),
+                    ("DOCKER_HOST", "tcp://example:2375".into()),
+                    ("DOCKER_TLS_VERIFY", "1".into()),
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn leaves_other_invocations_alone() {
+        for args in [
+            args(&["docker-debug", "echo", "hi"]),
+            args(&["docker-debug", "rebug", "alpine"]),
+            args(&["rizzybox", "debug", "alpine"]),
+            args(&["docker-debug", "--context"]),
+        ] {
+            // Act
+            let call = PluginCall::parse(&args).unwrap();
+
+            // Assert
+            assert_eq!(call, None, "{args:?}");
+        }
+    }
+
+    #[test]
+    fn takes_short_options_with_attached_values() {
+        // Arrange
+        let args = args(&[
+            "docker-debug",
+            "-Htcp://example:2375",
+            "-cremote",
+            "-l=debug",
+            "debug",
+            "alpine",
+        ]);
+
+        // Act
+        let call = PluginCall::parse(&args).unwrap().unwrap();
+
+        // Assert
+        assert_eq!(
+            call.env,
+            vec![
+                ("DOCKER_HOST", "tcp://example:2375".into()),
+                ("DOCKER_CONTEXT", "remote".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn hands_tls_options_on() {
+        // Arrange
+        let args = args(&[
+            "docker-debug",
+            "--tls",
+            "--tlsverify=false",
+            "--tlscacert",
+            "/certs/ca.pem",
+            "--tlscert=/certs/cert.pem",
+            "--tlskey",
+            "/certs/key.pem",
+            "debug",
+            "alpine",
+        ]);
+
+        // Act
+        let call = PluginCall::parse(&args).unwrap().unwrap();
+
+        // Assert
+        assert_eq!(
+            call.env,
+            vec![
+                ("DOCKER_TLS", "1".into()),
+                ("DOCKER_CERT_PATH", "/certs".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_tls_files_docker_cert_path_cannot_name() {
+        for args in [
+            args(&["docker-debug", "--tlscacert", "/certs/my-ca.pem", "debug"]),
+            args(&[
+                "docker-debug",
+                "--tlscacert=/certs/ca.pem",
+                "--tlskey=/keys/key.pem",
+                "debug",
+            ]),
+        ] {
+            // Act
+            let result = PluginCall::parse(&args);
+
+            // Assert
+            assert!(result.is_err(), "{args:?}");
+        }
+    }
+
+    #[test]
+    fn prints_metadata_as_json() {
+        // Arrange
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+
+        // Act
+        cmd.arg("docker-cli-plugin-metadata");
+
+        // Assert
+        let output = cmd.assert().success().get_output().stdout.clone();
+        let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(json["SchemaVersion"], "0.1.0");
+        assert_eq!(json["Version"], env!("CARGO_PKG_VERSION"));
+        assert!(json["URL"].as_str().unwrap().starts_with("https://"));
+    }
+
+    #[test]
+    fn runs_docker_with_its_host() {
+        // Arrange
+        let (plugin, root) = link_rizzybox("cli-plugins", "docker-debug");
+        let docker = plugin.with_file_name("docker");
+        // images are never pulled, so `image inspect` fails
+        fs::write(
+            &docker,
+            "#!/bin/sh\n[ \"$1\" = image ] && exit 1\necho \"$DOCKER_HOST $1\"\n",
+        )
+        .unwrap();
+        fs::set_permissions(&docker, fs::Permissions::from_mode(0o755)).unwrap();
+        let mut cmd = Command::new(&plugin);
+
+        // Act
+        cmd.env("DOCKER_CLI_PLUGIN_ORIGINAL_CLI_COMMAND", &docker)
+            .env_remove("RZ_CONTAINER_ENGINE")
+            .args([
+                "-H",
+                "tcp://example:2375",
+                "debug",
+                "--allow-dynamic",
+                "alpine",
+            ]);
+
+        // Assert
+        cmd.assert().success().stdout("tcp://example:2375 run\n");
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn refuses_to_run_outside_cli_plugins() {
+        // Arrange
+        let (plugin, root) = link_rizzybox("bin", "docker-debug");
+        let mut cmd = Command::new(&plugin);
+
+        // Act
+        cmd.env_remove("DOCKER_CLI_PLUGIN_ORIGINAL_CLI_COMMAND")
+            .args(["debug", "alpine"]);
+
+        // Assert
+        cmd.assert().code(1).stderr(predicates::str::contains(
+            "docker-debug is not installed in a cli-plugins directory",
+        ));
+        fs::remove_dir_all(root).unwrap();
+    }
+}
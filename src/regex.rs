@@ -0,0 +1,175 @@
+/// Turn a POSIX basic regular expression, or an extended one if `extended` is set,
+/// into fancy-regex's syntax. GNU's `\<` and `\>` word boundaries are kept, and so
+/// are the Perl-style escapes like `\w` and `\d` that fancy-regex knows.
+pub fn translate(pattern: &str, extended: bool) -> String {
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut out = String::with_capacity(pattern.len());
+    // whether nothing precedes this point in the current (sub)expression, where `*`
+    // has nothing to repeat and a basic regex's `^` is an anchor
+    let mut at_start = true;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        i += 1;
+        let mut starts_expression = false;
+        match c {
+            '\\' => {
+                let Some(&next) = chars.get(i) else {
+                    out.push_str(r"\\");
+                    break;
+                };
+                i += 1;
+                match next {
+                    '{' if !extended => match interval(&chars[i..], r"\}") {
+                        Some((bounds, len)) => {
+                            out.push_str(&bounds);
+                            i += len;
+                        }
+                        None => out.push_str(r"\{"),
+                    },
+                    '(' | '|' if !extended => {
+                        out.push(next);
+                        starts_expression = true;
+                    }
+                    ')' | '}' | '+' | '?' if !extended => out.push(next),
+                    '<' => out.push_str(r"\b(?=\w)"),
+                    '>' => out.push_str(r"\b(?<=\w)"),
+                    next if next.is_ascii_alphanumeric() => {
+                        out.push('\\');
+                        out.push(next);
+                    }
+                    next => push_literal(&mut out, next),
+                }
+            }
+            '[' => i += bracket(&chars[i..], &mut out),
+            '*' if at_start => out.push_str(r"\*"),
+            '.' | '*' => out.push(c),
+            '^' if extended || at_start => {
+                out.push('^');
+                starts_expression = at_start;
+            }
+            '$' if extended || basic_ends_here(&chars[i..]) => out.push('$'),
+            '{' if extended => match interval(&chars[i..], "}") {
+                Some((bounds, len)) => {
+                    out.push_str(&bounds);
+                    i += len;
+                }
+                None => out.push_str(r"\{"),
+            },
+            '(' | '|' if extended => {
+                out.push(c);
+                starts_expression = true;
+            }
+            ')' | '+' | '?' if extended => out.push(c),
+            c => push_literal(&mut out, c),
+        }
+        at_start = starts_expression;
+    }
+    out
+}
+
+/// Whether a basic regex's `$` followed by `rest` is an anchor rather than a literal
+fn basic_ends_here(rest: &[char]) -> bool {
+    matches!(rest, [] | ['\\', ')' | '|', ..])
+}
+
+/// The bounds of the interval `{m,n}` whose `{` precedes `rest` and that is closed by
+/// `close`, as fancy-regex writes them, along with how much of `rest` they took.
+/// `None` if it isn't one, so that the `{` is taken literally.
+fn interval(rest: &[char], close: &str) -> Option<(String, usize)> {
+    let close: Vec<char> = close.chars().collect();
+    let end = rest
+        .windows(close.len())
+        .position(|window| window == close)?;
+    let bounds: String = rest[..end].iter().collect();
+    let (min, max) = bounds.split_once(',').unwrap_or((&bounds, &bounds));
+    let is_number = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    if !(is_number(min) || min.is_empty() && is_number(max)) || !(max.is_empty() || is_number(max))
+    {
+        return None;
+    }
+    let min = if min.is_empty() { "0" } else { min };
+    let bounds = if bounds.contains(',') {
+        format!("{{{min},{max}}}")
+    } else {
+        format!("{{{min}}}")
+    };
+    Some((bounds, end + close.len()))
+}
+
+/// Copy the bracket expression whose `[` precedes `rest` to `out`, escaping what
+/// fancy-regex would read as nested classes or escapes. Returns how much of `rest`
+/// it took.
+fn bracket(rest: &[char], out: &mut String) -> usize {
+    out.push('[');
+    let mut i = 0;
+    if rest.first() == Some(&'^') {
+        out.push('^');
+        i += 1;
+    }
+    if rest.get(i) == Some(&']') {
+        out.push_str(r"\]");
+        i += 1;
+    }
+    while let Some(&c) = rest.get(i) {
+        i += 1;
+        match c {
+            ']' => {
+                out.push(']');
+                return i;
+            }
+            // character classes like `[:alpha:]` are the same in both
+            '[' if let Some(len) = class(&rest[i..]) => {
+                out.push('[');
+                out.extend(&rest[i..i + len]);
+                i += len;
+            }
+            '\\' | '[' | '&' | '~' => {
+                out.push('\\');
+                out.push(c);
+            }
+            c => out.push(c),
+        }
+    }
+    // an unclosed `[` is left for fancy-regex to complain about
+    i
+}
+
+/// The length of the character class like `:alpha:]` that `rest` starts with, if any
+fn class(rest: &[char]) -> Option<usize> {
+    let name = rest.strip_prefix(&[':'])?;
+    let end = name.iter().position(|c| !c.is_ascii_alphabetic())?;
+    name[end..].starts_with(&[':', ']']).then_some(end + 3)
+}
+
+fn push_literal(out: &mut String, c: char) {
+    out.push_str(&fancy_regex::escape(c.encode_utf8(&mut [0; 4])));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::translate;
+
+    #[test]
+    fn translates_posix_regexes() {
+        for (pattern, extended, expected) in [
+            (r"a\(b\)*c", false, "a(b)*c"),
+            ("a(b)+c?", false, r"a\(b\)\+c\?"),
+            (r"x\{2,\}", false, "x{2,}"),
+            ("*a^b$", false, r"\*a\^b$"),
+            (r"^a$\|^b", false, "^a$|^b"),
+            ("a{,3}|(*b)", true, "a{0,3}|(\\*b)"),
+            ("a{x", true, r"a\{x"),
+            (r"[]a\[:]", true, r"[\]a\\\[:]"),
+            ("[[:alpha:][]", true, r"[[:alpha:]\[]"),
+            ("[[:digit:]]", true, "[[:digit:]]"),
+            (r"\<word\>", true, r"\b(?=\w)word\b(?<=\w)"),
+        ] {
+            // Act
+            let translated = translate(pattern, extended);
+
+            // Assert
+            assert_eq!(translated, expected, "{pattern}");
+        }
+    }
+}